@@ -1,10 +1,107 @@
-use ratatui::widgets::ListState;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
 
-use crate::service::{fetch_logs, fetch_services, SystemdService};
+use ratatui::style::Color;
+use ratatui::widgets::{ListState, TableState};
 
+use crate::backend::{SystemBackend, SystemdBackend};
+use crate::dbus::UnitEvent;
+use crate::deps::{DependencyDirection, DependencyTree};
+use crate::dropin;
+use crate::journal::{
+    boot_label, Boot, JournalEntry, JournalSource, LogColumn, LogFollower, LogQuery,
+    PageDirection, PRIORITY_NAMES,
+};
+use crate::keymap::Keymap;
+use crate::loader::{Loaded, Loader};
+use crate::properties::UnitProperties;
+use crate::service::{Scope, ServiceAction, ServiceFilter, SystemdService, SystemdTimer};
+
+/// Default status filter choices; option 0 clears the filter
 pub const STATUS_OPTIONS: [&str; 5] = ["All", "running", "exited", "failed", "dead"];
 
-pub struct App {
+/// Default width of the unit list in percent while the side panel is open
+pub const LIST_WIDTH: u16 = 40;
+
+pub const TIME_PRESETS: [&str; 6] = [
+    "All time",
+    "Last 15 minutes",
+    "Last hour",
+    "Today",
+    "Current boot",
+    "Custom...",
+];
+
+pub const UNIT_TYPE_OPTIONS: [&str; 12] = [
+    "All",
+    "service",
+    "timer",
+    "socket",
+    "mount",
+    "automount",
+    "path",
+    "target",
+    "slice",
+    "scope",
+    "swap",
+    "device",
+];
+
+/// Auto-refresh intervals cycled with `R`; `None` turns auto-refresh off
+pub const REFRESH_INTERVALS: [Option<Duration>; 5] = [
+    None,
+    Some(Duration::from_secs(2)),
+    Some(Duration::from_secs(5)),
+    Some(Duration::from_secs(10)),
+    Some(Duration::from_secs(30)),
+];
+
+/// How long a row stays highlighted after its state changed
+pub const FLASH_DURATION: Duration = Duration::from_secs(2);
+
+/// Shortest gap between reloads caused by units being loaded or unloaded
+const UNIT_EVENT_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Default for `App::log_page_size`
+pub const LOG_PAGE_SIZE: usize = 1000;
+
+/// Most entries kept in `App::logs`; the far end is evicted beyond this
+pub const MAX_LOG_ENTRIES: usize = 10_000;
+
+/// A unit whose active/sub state changed during a refresh; kept until acknowledged
+pub struct StateChange {
+    /// "active/sub" before the first unacknowledged change
+    pub from: String,
+    /// How many refreshes saw a different state, so flapping units stand out
+    pub count: u32,
+    pub last_changed: Instant,
+}
+
+/// The main content shown between header and footer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Services,
+    Timers,
+}
+
+/// What the side panel is showing; scrolling and search work the same for each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelMode {
+    Logs,
+    UnitFile,
+    Properties,
+}
+
+/// One-line feedback shown in the footer until the next key press
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+}
+
+pub struct App<B: SystemdBackend = SystemBackend> {
+    pub view: View,
     pub services: Vec<SystemdService>,
     pub list_state: ListState,
     pub should_quit: bool,
@@ -12,23 +109,116 @@ pub struct App {
     pub search_query: String,
     pub search_mode: bool,
     pub filtered_indices: Vec<usize>,
-    pub logs: Vec<String>,
+    pub logs: Vec<JournalEntry>,
+    /// `logs` rendered with `log_columns`; what the panel scrolls and searches
+    pub log_lines: Vec<String>,
+    pub log_columns: Vec<LogColumn>,
+    /// Journal priority threshold; `None` shows every level
+    pub log_priority: Option<u8>,
+    pub show_priority_picker: bool,
+    pub priority_picker_state: ListState,
+    pub log_since: Option<String>,
+    pub log_until: Option<String>,
+    pub log_boot: Option<i32>,
+    pub boots: Vec<Boot>,
+    pub show_boot_picker: bool,
+    pub boot_picker_state: ListState,
+    pub show_time_picker: bool,
+    pub time_picker_state: ListState,
+    /// Text of the custom "since..until" prompt while it is open
+    pub time_input: Option<String>,
+    pub show_column_picker: bool,
+    pub column_picker_state: ListState,
+    /// Index into `logs` of the entry whose fields are shown in the detail modal
+    pub entry_detail: Option<usize>,
+    pub entry_detail_scroll: usize,
     pub logs_scroll: usize,
+    /// The journal has entries before the first / after the last loaded one
+    pub logs_has_older: bool,
+    pub logs_has_newer: bool,
+    /// Journal entries fetched at once, and per page when scrolled past either end
+    pub log_page_size: usize,
+    /// Streams new journal entries into `logs` while follow mode is on
+    pub follower: Option<LogFollower>,
+    /// Follow mode is on but the user scrolled away from the bottom
+    pub follow_paused: bool,
+    /// Lines the logs panel has room for; the main loop sets it from the terminal size
+    pub logs_visible_lines: usize,
     pub last_selected_service: Option<String>,
     pub status_filter: Option<String>,
+    /// Sub-states offered by the status picker after "All"
+    pub status_options: Vec<String>,
+    /// Configured colours by sub-state, overriding `SystemdService::status_color`
+    pub status_colors: HashMap<String, Color>,
+    /// Keys of the main views, as set in the `[keys]` config section
+    pub keymap: Keymap,
+    pub unit_type: Option<String>,
     pub show_logs: bool,
+    /// Width of the unit list in percent while the side panel is open
+    pub list_width: u16,
+    pub panel_mode: PanelMode,
+    pub unit_file: Vec<String>,
+    pub properties: Option<UnitProperties>,
+    pub properties_lines: Vec<String>,
+    /// Text the main loop should put on the clipboard
+    pub pending_copy: Option<String>,
     pub show_help: bool,
     pub show_status_picker: bool,
     pub status_picker_state: ListState,
+    pub show_type_picker: bool,
+    pub type_picker_state: ListState,
     pub log_search_query: String,
     pub log_search_mode: bool,
     pub log_search_matches: Vec<usize>,
     pub log_search_match_index: Option<usize>,
+    pub show_action_picker: bool,
+    pub action_picker_state: ListState,
+    /// Unit and action awaiting confirmation; the unit is fixed when the dialog opens
+    pub pending_action: Option<(String, ServiceAction)>,
+    pub message: Option<StatusMessage>,
+    pub deps: Option<DependencyTree>,
+    pub deps_state: ListState,
+    /// Unit whose override the main loop should open in $EDITOR
+    pub pending_edit: Option<String>,
+    pub timers: Vec<SystemdTimer>,
+    pub timers_state: TableState,
+    pub timers_error: Option<String>,
+    /// A unit list / logs request is running on a worker thread
+    pub services_loading: bool,
+    pub logs_loading: bool,
+    /// A page of older or newer entries is being fetched
+    pub logs_paging: bool,
+    /// The unit file or properties shown in the side panel are being fetched
+    pub panel_loading: bool,
+    pub timers_loading: bool,
+    /// Children of a dependency tree node are being fetched
+    pub deps_loading: bool,
+    /// Actions and daemon reloads started but not finished yet
+    pub jobs_running: usize,
+    /// Scope being connected to; `backend` still has the previous one until then
+    switching_scope: Option<Scope>,
+    /// Unit to select once the unit list being loaded arrives
+    pending_focus: Option<String>,
+    pub refresh_interval: Option<Duration>,
+    /// Capture mouse events; off leaves text selection to the terminal
+    pub mouse: bool,
+    last_refresh: Instant,
+    /// Units whose state changed since the user last acknowledged them
+    pub changes: HashMap<String, StateChange>,
+    /// Scope and unit type `services` was loaded for; changes are only tracked within one list
+    services_source: Option<(Scope, Option<String>)>,
+    /// D-Bus if available, else systemctl
+    pub backend: B,
+    unit_events: Option<Receiver<UnitEvent>>,
+    /// A unit was loaded or unloaded since the list was fetched
+    services_stale: bool,
+    loader: Loader<B>,
 }
 
-impl App {
-    pub fn new() -> Self {
+impl<B: SystemdBackend> App<B> {
+    pub fn new(backend: B) -> Self {
         let mut app = Self {
+            view: View::Services,
             services: Vec::new(),
             list_state: ListState::default(),
             should_quit: false,
@@ -37,28 +227,209 @@ impl App {
             search_mode: false,
             filtered_indices: Vec::new(),
             logs: Vec::new(),
+            log_lines: Vec::new(),
+            log_columns: LogColumn::DEFAULT.to_vec(),
+            log_priority: None,
+            show_priority_picker: false,
+            priority_picker_state: ListState::default(),
+            log_since: None,
+            log_until: None,
+            log_boot: None,
+            boots: Vec::new(),
+            show_boot_picker: false,
+            boot_picker_state: ListState::default(),
+            show_time_picker: false,
+            time_picker_state: ListState::default(),
+            time_input: None,
+            show_column_picker: false,
+            column_picker_state: ListState::default(),
+            entry_detail: None,
+            entry_detail_scroll: 0,
             logs_scroll: 0,
+            logs_has_older: false,
+            logs_has_newer: false,
+            log_page_size: LOG_PAGE_SIZE,
+            follower: None,
+            follow_paused: false,
+            logs_visible_lines: 0,
             last_selected_service: None,
             status_filter: None,
+            status_options: STATUS_OPTIONS[1..].iter().map(|s| s.to_string()).collect(),
+            status_colors: HashMap::new(),
+            keymap: Keymap::default(),
+            unit_type: Some("service".to_string()),
             show_logs: false,
+            list_width: LIST_WIDTH,
+            panel_mode: PanelMode::Logs,
+            unit_file: Vec::new(),
+            properties: None,
+            properties_lines: Vec::new(),
+            pending_copy: None,
             show_help: false,
             show_status_picker: false,
             status_picker_state: ListState::default(),
+            show_type_picker: false,
+            type_picker_state: ListState::default(),
             log_search_query: String::new(),
             log_search_mode: false,
             log_search_matches: Vec::new(),
             log_search_match_index: None,
+            show_action_picker: false,
+            action_picker_state: ListState::default(),
+            pending_action: None,
+            message: None,
+            deps: None,
+            deps_state: ListState::default(),
+            pending_edit: None,
+            timers: Vec::new(),
+            timers_state: TableState::default(),
+            timers_error: None,
+            services_loading: false,
+            logs_loading: false,
+            logs_paging: false,
+            panel_loading: false,
+            timers_loading: false,
+            deps_loading: false,
+            jobs_running: 0,
+            switching_scope: None,
+            pending_focus: None,
+            refresh_interval: None,
+            mouse: true,
+            last_refresh: Instant::now(),
+            changes: HashMap::new(),
+            services_source: None,
+            unit_events: backend.subscribe(),
+            backend,
+            services_stale: false,
+            loader: Loader::new(),
         };
         app.load_services();
         app
     }
 
+    /// Starts fetching the unit list in the background; see `poll_loader`
     pub fn load_services(&mut self) {
-        match fetch_services() {
+        self.services_loading = true;
+        self.last_refresh = Instant::now();
+        self.loader.load_services(self.backend.clone(), self.unit_type.clone());
+    }
+
+    /// Runs once per main loop iteration: applies finished background loads,
+    /// streams followed entries and starts a logs load when the selection changed
+    pub fn tick(&mut self) {
+        self.poll_loader();
+        self.poll_unit_events();
+        self.poll_follower();
+        self.pin_follow();
+        if let Some(interval) = self.refresh_interval
+            && self.view == View::Services
+            && !self.services_loading
+            && self.last_refresh.elapsed() >= interval
+        {
+            self.load_services();
+        }
+        if self.view == View::Services && self.show_logs && self.panel_mode == PanelMode::Logs {
+            self.load_logs_for_selected();
+        }
+    }
+
+    fn poll_loader(&mut self) {
+        for loaded in self.loader.drain() {
+            match loaded {
+                Loaded::Services { result, .. } => self.apply_services(result),
+                Loaded::Logs { unit, result, .. } => {
+                    if self.last_selected_service.as_deref() == Some(unit.as_str()) {
+                        self.apply_logs(result);
+                    }
+                }
+                Loaded::LogPage {
+                    unit,
+                    cursor,
+                    direction,
+                    result,
+                    ..
+                } => {
+                    if self.last_selected_service.as_deref() == Some(unit.as_str()) {
+                        self.apply_log_page(&cursor, direction, result);
+                    }
+                }
+                Loaded::Service { unit, result, .. } => self.apply_service(&unit, result),
+                Loaded::UnitFile { result, .. } => {
+                    self.panel_loading = false;
+                    self.unit_file = match result {
+                        Ok(lines) => lines,
+                        Err(e) => vec![format!("Error fetching unit file: {}", e)],
+                    };
+                }
+                Loaded::Properties { result, .. } => {
+                    self.panel_loading = false;
+                    match result {
+                        Ok(properties) => {
+                            self.properties_lines = properties.to_lines();
+                            self.properties = Some(properties);
+                        }
+                        Err(e) => {
+                            self.properties_lines =
+                                vec![format!("Error fetching properties: {}", e)];
+                            self.properties = None;
+                        }
+                    }
+                }
+                Loaded::Timers { result, .. } => self.apply_timers(result),
+                Loaded::Boots { result, .. } => self.apply_boots(result),
+                Loaded::Dependencies {
+                    index,
+                    unit,
+                    direction,
+                    result,
+                    ..
+                } => self.apply_dependencies(index, &unit, direction, result),
+                Loaded::Action {
+                    unit,
+                    action,
+                    result,
+                } => self.apply_action(&unit, action, result),
+                Loaded::Reload { unit, path, result } => self.apply_reload(&unit, &path, result),
+                Loaded::Scope {
+                    backend, events, ..
+                } => self.apply_scope(backend, events),
+            }
+        }
+    }
+
+    /// Whether a background request has not been applied yet
+    pub fn busy(&self) -> bool {
+        self.loader.busy()
+            || self.services_loading
+            || self.logs_loading
+            || self.logs_paging
+            || self.panel_loading
+            || self.timers_loading
+            || self.deps_loading
+            || self.jobs_running > 0
+            || self.switching_scope.is_some()
+    }
+
+    fn apply_services(&mut self, result: Result<Vec<SystemdService>, String>) {
+        self.services_loading = false;
+        match result {
             Ok(services) => {
+                let source = (self.backend.scope(), self.unit_type.clone());
+                if self.services_source.as_ref() == Some(&source) {
+                    self.record_changes(&services);
+                } else {
+                    self.changes.clear();
+                }
+                self.services_source = Some(source);
+
+                let selected = self.selected_service().map(|s| s.unit.clone());
                 self.services = services;
                 self.error = None;
                 self.update_filter();
+                // Keep the same unit selected when refreshing shifts rows around
+                if let Some(unit) = selected {
+                    self.reselect(&unit);
+                }
                 if !self.filtered_indices.is_empty() && self.list_state.selected().is_none() {
                     self.list_state.select(Some(0));
                 }
@@ -67,26 +438,185 @@ impl App {
                 self.error = Some(e);
             }
         }
+        if let Some(unit) = self.pending_focus.take() {
+            self.select_unit(&unit);
+        }
+    }
+
+    fn reselect(&mut self, unit: &str) {
+        if let Some(pos) = self
+            .filtered_indices
+            .iter()
+            .position(|&i| self.services[i].unit == unit)
+        {
+            self.list_state.select(Some(pos));
+        }
+    }
+
+    /// Marks units whose active/sub state differs from the currently loaded list
+    fn record_changes(&mut self, services: &[SystemdService]) {
+        let previous: HashMap<&str, &SystemdService> = self
+            .services
+            .iter()
+            .map(|s| (s.unit.as_str(), s))
+            .collect();
+        let changed: Vec<(String, String)> = services
+            .iter()
+            .filter_map(|service| {
+                let old = previous.get(service.unit.as_str())?;
+                (old.active != service.active || old.sub != service.sub)
+                    .then(|| (service.unit.clone(), format!("{}/{}", old.active, old.sub)))
+            })
+            .collect();
+        for (unit, from) in changed {
+            self.mark_changed(unit, from);
+        }
+    }
+
+    fn mark_changed(&mut self, unit: String, from: String) {
+        let now = Instant::now();
+        let change = self.changes.entry(unit).or_insert_with(|| StateChange {
+            from,
+            count: 0,
+            last_changed: now,
+        });
+        change.count += 1;
+        change.last_changed = now;
+    }
+
+    /// Applies unit signals from the D-Bus backend: state changes update rows in place,
+    /// loaded or unloaded units trigger a (debounced) reload of the list
+    fn poll_unit_events(&mut self) {
+        let Some(ref events) = self.unit_events else {
+            return;
+        };
+        let events: Vec<UnitEvent> = events.try_iter().collect();
+        let suffix = self.unit_type.as_ref().map(|t| format!(".{}", t));
+        let mut state_changed = false;
+        for event in events {
+            match event {
+                UnitEvent::StateChanged { unit, active, sub } => {
+                    let Some(service) = self.services.iter_mut().find(|s| s.unit == unit) else {
+                        continue;
+                    };
+                    let from = format!("{}/{}", service.active, service.sub);
+                    if let Some(active) = active {
+                        service.active = active;
+                    }
+                    if let Some(sub) = sub {
+                        service.sub = sub;
+                    }
+                    if format!("{}/{}", service.active, service.sub) != from {
+                        self.mark_changed(unit, from);
+                        state_changed = true;
+                    }
+                }
+                UnitEvent::Added(unit) | UnitEvent::Removed(unit) => {
+                    if suffix.as_deref().is_none_or(|suffix| unit.ends_with(suffix)) {
+                        self.services_stale = true;
+                    }
+                }
+            }
+        }
+
+        if state_changed {
+            // The status filter may now include or exclude rows
+            let selected = self.selected_service().map(|s| s.unit.clone());
+            self.update_filter();
+            if let Some(unit) = selected {
+                self.reselect(&unit);
+            }
+        }
+        if self.services_stale
+            && !self.services_loading
+            && self.last_refresh.elapsed() >= UNIT_EVENT_DEBOUNCE
+        {
+            self.services_stale = false;
+            self.load_services();
+        }
+    }
+
+    /// Cycles the auto-refresh interval through `REFRESH_INTERVALS`
+    pub fn cycle_refresh_interval(&mut self) {
+        let current = REFRESH_INTERVALS
+            .iter()
+            .position(|&i| i == self.refresh_interval)
+            .unwrap_or(0);
+        self.refresh_interval = REFRESH_INTERVALS[(current + 1) % REFRESH_INTERVALS.len()];
+        self.last_refresh = Instant::now();
+        self.message = Some(StatusMessage {
+            text: match self.refresh_interval {
+                Some(interval) => format!("Auto-refresh every {}s", interval.as_secs()),
+                None => "Auto-refresh off".to_string(),
+            },
+            is_error: false,
+        });
+    }
+
+    /// Clears the change mark of the selected unit
+    pub fn acknowledge_change(&mut self) {
+        if let Some(unit) = self.selected_service().map(|s| s.unit.clone()) {
+            self.changes.remove(&unit);
+        }
+    }
+
+    pub fn acknowledge_all_changes(&mut self) {
+        self.changes.clear();
+    }
+
+    /// Plural heading for the units being browsed, e.g. "Services" or "Timers"
+    pub fn unit_type_title(&self) -> String {
+        match self.unit_type.as_deref() {
+            None => "Units".to_string(),
+            Some(t) => {
+                let mut chars = t.chars();
+                match chars.next() {
+                    Some(first) => format!("{}{}s", first.to_uppercase(), chars.as_str()),
+                    None => "Units".to_string(),
+                }
+            }
+        }
+    }
+
+    /// Switches between the system and user service managers; connecting to the
+    /// other one happens in the background, see `apply_scope`
+    pub fn toggle_scope(&mut self) {
+        if self.backend.fixed_scope() {
+            self.message = Some(StatusMessage {
+                text: format!(
+                    "The {} only holds {} units",
+                    self.backend.label(),
+                    self.backend.scope().label()
+                ),
+                is_error: true,
+            });
+            return;
+        }
+        let scope = self.switching_scope.unwrap_or_else(|| self.backend.scope()).toggle();
+        self.switching_scope = Some(scope);
+        self.unit_events = None;
+        self.services.clear();
+        self.list_state.select(None);
+        self.update_filter();
+        self.last_selected_service = None;
+        self.services_loading = true;
+        self.loader.switch_scope(self.backend.clone(), scope);
+    }
+
+    fn apply_scope(&mut self, backend: B, events: Option<Receiver<UnitEvent>>) {
+        self.switching_scope = None;
+        self.backend = backend;
+        self.unit_events = events;
+        self.load_services();
     }
 
     pub fn update_filter(&mut self) {
-        let query = self.search_query.to_lowercase();
+        let filter = ServiceFilter::new(&self.search_query, self.status_filter.as_deref());
         self.filtered_indices = self
             .services
             .iter()
             .enumerate()
-            .filter(|(_, service)| {
-                // Text search filter
-                let matches_search = self.search_query.is_empty()
-                    || service.unit.to_lowercase().contains(&query)
-                    || service.description.to_lowercase().contains(&query);
-
-                // Status filter
-                let matches_status = self.status_filter.is_none()
-                    || self.status_filter.as_ref() == Some(&service.sub);
-
-                matches_search && matches_status
-            })
+            .filter(|(_, service)| filter.matches(service))
             .map(|(i, _)| i)
             .collect();
 
@@ -104,6 +634,14 @@ impl App {
         }
     }
 
+    /// Colour of a unit's state, taken from the config if it sets one for the sub-state
+    pub fn status_color(&self, service: &SystemdService) -> Color {
+        self.status_colors
+            .get(&service.sub)
+            .copied()
+            .unwrap_or_else(|| service.status_color())
+    }
+
     pub fn clear_search(&mut self) {
         self.search_query.clear();
         self.update_filter();
@@ -114,10 +652,11 @@ impl App {
         // Pre-select the current filter
         let index = match &self.status_filter {
             None => 0, // "All"
-            Some(s) => STATUS_OPTIONS
+            Some(s) => self
+                .status_options
                 .iter()
-                .position(|&opt| opt == s)
-                .unwrap_or(0),
+                .position(|opt| opt == s)
+                .map_or(0, |i| i + 1),
         };
         self.status_picker_state.select(Some(index));
     }
@@ -128,14 +667,14 @@ impl App {
 
     pub fn status_picker_next(&mut self) {
         let i = self.status_picker_state.selected().unwrap_or(0);
-        let next = (i + 1) % STATUS_OPTIONS.len();
+        let next = (i + 1) % (self.status_options.len() + 1);
         self.status_picker_state.select(Some(next));
     }
 
     pub fn status_picker_previous(&mut self) {
         let i = self.status_picker_state.selected().unwrap_or(0);
         let prev = if i == 0 {
-            STATUS_OPTIONS.len() - 1
+            self.status_options.len()
         } else {
             i - 1
         };
@@ -147,198 +686,1797 @@ impl App {
             if i == 0 {
                 self.status_filter = None;
             } else {
-                self.status_filter = Some(STATUS_OPTIONS[i].to_string());
+                self.status_filter = self.status_options.get(i - 1).cloned();
             }
             self.update_filter();
         }
         self.show_status_picker = false;
     }
 
-    pub fn next(&mut self) {
-        if self.filtered_indices.is_empty() {
-            return;
-        }
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i >= self.filtered_indices.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
+    pub fn open_type_picker(&mut self) {
+        self.show_type_picker = true;
+        // Pre-select the current unit type
+        let index = match &self.unit_type {
+            None => 0, // "All"
+            Some(t) => UNIT_TYPE_OPTIONS
+                .iter()
+                .position(|&opt| opt == t)
+                .unwrap_or(0),
         };
-        self.list_state.select(Some(i));
+        self.type_picker_state.select(Some(index));
     }
 
-    pub fn previous(&mut self) {
-        if self.filtered_indices.is_empty() {
-            return;
-        }
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.filtered_indices.len() - 1
-                } else {
-                    i - 1
-                }
-            }
-            None => 0,
+    pub fn close_type_picker(&mut self) {
+        self.show_type_picker = false;
+    }
+
+    pub fn type_picker_next(&mut self) {
+        let i = self.type_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % UNIT_TYPE_OPTIONS.len();
+        self.type_picker_state.select(Some(next));
+    }
+
+    pub fn type_picker_previous(&mut self) {
+        let i = self.type_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 {
+            UNIT_TYPE_OPTIONS.len() - 1
+        } else {
+            i - 1
         };
-        self.list_state.select(Some(i));
+        self.type_picker_state.select(Some(prev));
     }
 
-    pub fn go_to_top(&mut self) {
-        if !self.filtered_indices.is_empty() {
-            self.list_state.select(Some(0));
+    pub fn type_picker_confirm(&mut self) {
+        if let Some(i) = self.type_picker_state.selected() {
+            let unit_type = if i == 0 {
+                None
+            } else {
+                Some(UNIT_TYPE_OPTIONS[i].to_string())
+            };
+            if unit_type != self.unit_type {
+                self.unit_type = unit_type;
+                self.list_state.select(None);
+                self.load_services();
+            }
         }
+        self.show_type_picker = false;
     }
 
-    pub fn go_to_bottom(&mut self) {
-        if !self.filtered_indices.is_empty() {
-            self.list_state.select(Some(self.filtered_indices.len() - 1));
-        }
+    /// Whether a modal overlay (picker or confirm dialog) is capturing input
+    pub fn modal_open(&self) -> bool {
+        self.show_status_picker
+            || self.show_type_picker
+            || self.show_action_picker
+            || self.pending_action.is_some()
+            || self.deps.is_some()
+            || self.show_column_picker
+            || self.show_priority_picker
+            || self.show_boot_picker
+            || self.show_time_picker
+            || self.time_input.is_some()
+            || self.entry_detail.is_some()
     }
 
-    pub fn selected_service(&self) -> Option<&SystemdService> {
-        self.list_state
-            .selected()
-            .and_then(|i| self.filtered_indices.get(i))
-            .map(|&i| &self.services[i])
+    pub fn open_priority_picker(&mut self) {
+        self.show_priority_picker = true;
+        // Option 0 is "All", option n + 1 is priority n
+        let index = self.log_priority.map_or(0, |p| p as usize + 1);
+        self.priority_picker_state.select(Some(index));
     }
 
-    pub fn load_logs_for_selected(&mut self) {
-        let current_service = self.selected_service().map(|s| s.unit.clone());
+    pub fn close_priority_picker(&mut self) {
+        self.show_priority_picker = false;
+    }
 
-        if current_service != self.last_selected_service {
-            self.last_selected_service = current_service.clone();
-            self.logs_scroll = 0;
-            self.clear_log_search();
+    pub fn priority_picker_next(&mut self) {
+        let i = self.priority_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % (PRIORITY_NAMES.len() + 1);
+        self.priority_picker_state.select(Some(next));
+    }
 
-            if let Some(unit) = current_service {
-                match fetch_logs(&unit, 1000) {
-                    Ok(logs) => {
-                        self.logs = logs;
-                        // Auto-scroll to bottom (most recent logs)
-                        if !self.logs.is_empty() {
-                            self.logs_scroll = self.logs.len().saturating_sub(1);
-                        }
-                    }
-                    Err(e) => {
-                        self.logs = vec![format!("Error fetching logs: {}", e)];
-                    }
-                }
-            } else {
-                self.logs.clear();
+    pub fn priority_picker_previous(&mut self) {
+        let i = self.priority_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 { PRIORITY_NAMES.len() } else { i - 1 };
+        self.priority_picker_state.select(Some(prev));
+    }
+
+    /// Applies the picked threshold and re-queries the journal with it
+    pub fn priority_picker_confirm(&mut self) {
+        if let Some(i) = self.priority_picker_state.selected() {
+            let priority = i.checked_sub(1).map(|p| p as u8);
+            if priority != self.log_priority {
+                self.log_priority = priority;
+                self.reload_logs();
             }
         }
+        self.show_priority_picker = false;
     }
 
-    pub fn scroll_logs_up(&mut self, amount: usize) {
-        self.logs_scroll = self.logs_scroll.saturating_sub(amount);
+    /// Lists the journal's boots; option 0 is "All boots", option n + 1 is `boots[n]`
+    pub fn open_boot_picker(&mut self) {
+        if !self.require_journal("Boots") {
+            return;
+        }
+        self.loader.load_boots(self.backend.clone());
     }
 
-    pub fn scroll_logs_down(&mut self, amount: usize, visible_lines: usize) {
-        if !self.logs.is_empty() {
-            let max_scroll = self.logs.len().saturating_sub(visible_lines);
-            self.logs_scroll = (self.logs_scroll + amount).min(max_scroll);
+    fn apply_boots(&mut self, result: Result<Vec<Boot>, String>) {
+        match result {
+            // Opening the picker over another dialog would steal its keys
+            Ok(_) if self.modal_open() => {}
+            Ok(boots) => {
+                let index = self
+                    .log_boot
+                    .and_then(|offset| boots.iter().position(|b| b.index == offset))
+                    .map_or(0, |i| i + 1);
+                self.boots = boots;
+                self.boot_picker_state.select(Some(index));
+                self.show_boot_picker = true;
+            }
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: format!("Cannot list boots: {}", e),
+                    is_error: true,
+                });
+            }
         }
     }
 
-    pub fn toggle_logs(&mut self) {
-        self.show_logs = !self.show_logs;
+    pub fn close_boot_picker(&mut self) {
+        self.show_boot_picker = false;
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    pub fn boot_picker_next(&mut self) {
+        let i = self.boot_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % (self.boots.len() + 1);
+        self.boot_picker_state.select(Some(next));
     }
 
-    pub fn page_up(&mut self, page_size: usize) {
-        if self.filtered_indices.is_empty() {
-            return;
-        }
-        let current = self.list_state.selected().unwrap_or(0);
-        let new_index = current.saturating_sub(page_size);
-        self.list_state.select(Some(new_index));
+    pub fn boot_picker_previous(&mut self) {
+        let i = self.boot_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 { self.boots.len() } else { i - 1 };
+        self.boot_picker_state.select(Some(prev));
     }
 
-    pub fn page_down(&mut self, page_size: usize) {
-        if self.filtered_indices.is_empty() {
-            return;
+    /// Scopes the logs to the picked boot and re-queries the journal
+    pub fn boot_picker_confirm(&mut self) {
+        if let Some(i) = self.boot_picker_state.selected() {
+            let boot = i.checked_sub(1).and_then(|b| self.boots.get(b)).map(|b| b.index);
+            if boot != self.log_boot {
+                self.log_boot = boot;
+                self.reload_logs();
+            }
         }
-        let current = self.list_state.selected().unwrap_or(0);
-        let max_index = self.filtered_indices.len().saturating_sub(1);
-        let new_index = (current + page_size).min(max_index);
-        self.list_state.select(Some(new_index));
+        self.show_boot_picker = false;
     }
 
-    pub fn update_log_search(&mut self) {
-        self.log_search_matches.clear();
-        self.log_search_match_index = None;
-
-        if self.log_search_query.is_empty() {
-            return;
-        }
+    pub fn open_time_picker(&mut self) {
+        self.show_time_picker = true;
+        self.time_picker_state.select(Some(0));
+    }
 
-        let query = self.log_search_query.to_lowercase();
-        for (i, line) in self.logs.iter().enumerate() {
-            if line.to_lowercase().contains(&query) {
-                self.log_search_matches.push(i);
-            }
-        }
+    pub fn close_time_picker(&mut self) {
+        self.show_time_picker = false;
+    }
 
-        // Auto-scroll to first match
-        if !self.log_search_matches.is_empty() {
-            self.log_search_match_index = Some(0);
-            self.logs_scroll = self.log_search_matches[0];
-        }
+    pub fn time_picker_next(&mut self) {
+        let i = self.time_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % TIME_PRESETS.len();
+        self.time_picker_state.select(Some(next));
     }
 
-    pub fn clear_log_search(&mut self) {
-        self.log_search_query.clear();
-        self.log_search_matches.clear();
-        self.log_search_match_index = None;
+    pub fn time_picker_previous(&mut self) {
+        let i = self.time_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 { TIME_PRESETS.len() - 1 } else { i - 1 };
+        self.time_picker_state.select(Some(prev));
     }
 
-    pub fn next_log_match(&mut self, visible_lines: usize) {
-        if self.log_search_matches.is_empty() {
+    pub fn time_picker_confirm(&mut self) {
+        self.show_time_picker = false;
+        let selected = self.time_picker_state.selected();
+        if selected == Some(TIME_PRESETS.len() - 1) {
+            // Custom: prefill the prompt with the current window
+            self.time_input = Some(match (&self.log_since, &self.log_until) {
+                (None, None) => String::new(),
+                (since, until) => format!(
+                    "{}..{}",
+                    since.as_deref().unwrap_or(""),
+                    until.as_deref().unwrap_or("")
+                ),
+            });
             return;
         }
-        let next = match self.log_search_match_index {
-            Some(i) => (i + 1) % self.log_search_matches.len(),
-            None => 0,
+        let since = match selected {
+            Some(1) => Some("-15min".to_string()),
+            Some(2) => Some("-1h".to_string()),
+            Some(3) => Some("today".to_string()),
+            _ => None,
         };
-        self.log_search_match_index = Some(next);
-        let line_idx = self.log_search_matches[next];
-        // Scroll so match is visible
-        if line_idx < self.logs_scroll || line_idx >= self.logs_scroll + visible_lines {
-            self.logs_scroll = line_idx;
-        }
+        let boot = (selected == Some(4)).then_some(0);
+        self.set_time_range(since, None, boot);
     }
 
-    pub fn prev_log_match(&mut self, visible_lines: usize) {
-        if self.log_search_matches.is_empty() {
+    pub fn cancel_time_input(&mut self) {
+        self.time_input = None;
+    }
+
+    /// Applies a typed range: "since", "since..until" or "..until"
+    pub fn confirm_time_input(&mut self) {
+        let Some(input) = self.time_input.take() else {
             return;
-        }
-        let prev = match self.log_search_match_index {
-            Some(0) => self.log_search_matches.len() - 1,
-            Some(i) => i - 1,
-            None => self.log_search_matches.len() - 1,
         };
-        self.log_search_match_index = Some(prev);
-        let line_idx = self.log_search_matches[prev];
-        if line_idx < self.logs_scroll || line_idx >= self.logs_scroll + visible_lines {
-            self.logs_scroll = line_idx;
-        }
+        let (since, until) = match input.split_once("..") {
+            Some((since, until)) => (since.trim(), until.trim()),
+            None => (input.trim(), ""),
+        };
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        self.set_time_range(non_empty(since), non_empty(until), self.log_boot);
     }
 
-    pub fn logs_go_to_top(&mut self) {
-        self.logs_scroll = 0;
+    fn set_time_range(&mut self, since: Option<String>, until: Option<String>, boot: Option<i32>) {
+        self.log_since = since;
+        self.log_until = until;
+        self.log_boot = boot;
+        self.reload_logs();
     }
 
-    pub fn logs_go_to_bottom(&mut self, visible_lines: usize) {
-        if !self.logs.is_empty() {
-            self.logs_scroll = self.logs.len().saturating_sub(visible_lines);
+    /// Short description of the active time window for the logs title
+    pub fn log_window_label(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(ref since) = self.log_since {
+            parts.push(format!("since {}", since));
+        }
+        if let Some(ref until) = self.log_until {
+            parts.push(format!("until {}", until));
         }
+        if let Some(boot) = self.log_boot {
+            parts.push(boot_label(boot));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// Forces the logs panel to re-query the journal on the next draw
+    pub fn reload_logs(&mut self) {
+        self.last_selected_service = None;
+    }
+
+    pub fn open_column_picker(&mut self) {
+        self.show_column_picker = true;
+        self.column_picker_state.select(Some(0));
+    }
+
+    pub fn close_column_picker(&mut self) {
+        self.show_column_picker = false;
+    }
+
+    pub fn column_picker_next(&mut self) {
+        let i = self.column_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % LogColumn::ALL.len();
+        self.column_picker_state.select(Some(next));
+    }
+
+    pub fn column_picker_previous(&mut self) {
+        let i = self.column_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 {
+            LogColumn::ALL.len() - 1
+        } else {
+            i - 1
+        };
+        self.column_picker_state.select(Some(prev));
+    }
+
+    /// Shows or hides the highlighted column, keeping columns in their canonical order
+    pub fn column_picker_toggle(&mut self) {
+        let Some(i) = self.column_picker_state.selected() else {
+            return;
+        };
+        let column = LogColumn::ALL[i];
+        if self.log_columns.contains(&column) {
+            self.log_columns.retain(|&c| c != column);
+        } else {
+            self.log_columns.push(column);
+            self.log_columns.sort_by_key(|c| LogColumn::ALL.iter().position(|a| a == c));
+        }
+        self.rebuild_log_lines();
+    }
+
+    /// Opens the field list of the highlighted log entry
+    pub fn open_entry_detail(&mut self) {
+        if self.panel_mode != PanelMode::Logs {
+            return;
+        }
+        if let Some(i) = self
+            .current_panel_line()
+            .filter(|&i| !self.logs[i].fields.is_empty())
+        {
+            self.entry_detail = Some(i);
+            self.entry_detail_scroll = 0;
+        }
+    }
+
+    pub fn close_entry_detail(&mut self) {
+        self.entry_detail = None;
+    }
+
+    pub fn entry_detail_scroll_down(&mut self, amount: usize) {
+        let fields = self
+            .entry_detail
+            .and_then(|i| self.logs.get(i))
+            .map_or(0, |entry| entry.fields.len());
+        self.entry_detail_scroll =
+            (self.entry_detail_scroll + amount).min(fields.saturating_sub(1));
+    }
+
+    pub fn entry_detail_scroll_up(&mut self, amount: usize) {
+        self.entry_detail_scroll = self.entry_detail_scroll.saturating_sub(amount);
+    }
+
+    /// Opens the dependency tree of the selected unit with its first level expanded
+    pub fn open_deps(&mut self) {
+        let Some(root) = self.selected_service().cloned() else {
+            return;
+        };
+        if !self.require_live("Dependencies") {
+            return;
+        }
+        self.build_deps(root, DependencyDirection::Requires);
+    }
+
+    fn build_deps(&mut self, root: SystemdService, direction: DependencyDirection) {
+        self.loader.discard_dependencies();
+        self.deps_loading = false;
+        self.deps = Some(DependencyTree::new(root, direction));
+        self.deps_state.select(Some(0));
+        self.deps_expand();
+    }
+
+    pub fn close_deps(&mut self) {
+        self.loader.discard_dependencies();
+        self.deps_loading = false;
+        self.deps = None;
+    }
+
+    /// Rebuilds the tree from the same root following the next kind of edge
+    pub fn deps_cycle_direction(&mut self) {
+        if let Some(tree) = self.deps.take() {
+            let direction = tree.direction.next();
+            let root = tree.nodes.into_iter().next().map(|n| n.service);
+            if let Some(root) = root {
+                self.build_deps(root, direction);
+            }
+        }
+    }
+
+    pub fn deps_next(&mut self) {
+        let Some(ref tree) = self.deps else { return };
+        let i = self.deps_state.selected().unwrap_or(0);
+        self.deps_state.select(Some((i + 1) % tree.nodes.len()));
+    }
+
+    pub fn deps_previous(&mut self) {
+        let Some(ref tree) = self.deps else { return };
+        let i = self.deps_state.selected().unwrap_or(0);
+        let prev = if i == 0 { tree.nodes.len() - 1 } else { i - 1 };
+        self.deps_state.select(Some(prev));
+    }
+
+    /// Starts fetching the children of the selected node; see `apply_dependencies`
+    pub fn deps_expand(&mut self) {
+        let Some(ref tree) = self.deps else { return };
+        let i = self.deps_state.selected().unwrap_or(0);
+        let node = &tree.nodes[i];
+        if node.expanded || node.cycle {
+            return;
+        }
+        self.deps_loading = true;
+        self.loader.load_dependencies(
+            self.backend.clone(),
+            i,
+            node.service.unit.clone(),
+            tree.direction,
+        );
+    }
+
+    /// Expands the node the children were fetched for, if the tree still has it there
+    fn apply_dependencies(
+        &mut self,
+        index: usize,
+        unit: &str,
+        direction: DependencyDirection,
+        result: Result<Vec<SystemdService>, String>,
+    ) {
+        self.deps_loading = false;
+        let Some(ref mut tree) = self.deps else { return };
+        let same_node = tree.direction == direction
+            && tree.nodes.get(index).is_some_and(|n| n.service.unit == unit);
+        match result {
+            Ok(children) if same_node => tree.expand(index, children),
+            Ok(_) => {}
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: e,
+                    is_error: true,
+                });
+            }
+        }
+    }
+
+    /// Collapses the selected node, or moves to its parent if it is already collapsed
+    pub fn deps_collapse(&mut self) {
+        let Some(ref mut tree) = self.deps else { return };
+        let i = self.deps_state.selected().unwrap_or(0);
+        if tree.nodes[i].expanded {
+            tree.collapse(i);
+        } else if let Some(parent) = tree.parent(i) {
+            self.deps_state.select(Some(parent));
+        }
+    }
+
+    pub fn deps_toggle(&mut self) {
+        let expanded = self.deps.as_ref().is_some_and(|tree| {
+            tree.nodes[self.deps_state.selected().unwrap_or(0)].expanded
+        });
+        if expanded {
+            self.deps_collapse();
+        } else {
+            self.deps_expand();
+        }
+    }
+
+    /// Closes the tree and selects the highlighted unit in the main list
+    pub fn deps_select(&mut self) {
+        let unit = self.deps.as_ref().and_then(|tree| {
+            tree.nodes
+                .get(self.deps_state.selected().unwrap_or(0))
+                .map(|n| n.service.unit.clone())
+        });
+        self.deps = None;
+        if let Some(unit) = unit {
+            self.focus_unit(&unit);
+        }
+    }
+
+    pub fn open_action_picker(&mut self) {
+        if self.selected_service().is_none() || !self.require_live("Actions") {
+            return;
+        }
+        self.show_action_picker = true;
+        self.action_picker_state.select(Some(0));
+    }
+
+    pub fn close_action_picker(&mut self) {
+        self.show_action_picker = false;
+    }
+
+    pub fn action_picker_next(&mut self) {
+        let i = self.action_picker_state.selected().unwrap_or(0);
+        let next = (i + 1) % ServiceAction::ALL.len();
+        self.action_picker_state.select(Some(next));
+    }
+
+    pub fn action_picker_previous(&mut self) {
+        let i = self.action_picker_state.selected().unwrap_or(0);
+        let prev = if i == 0 {
+            ServiceAction::ALL.len() - 1
+        } else {
+            i - 1
+        };
+        self.action_picker_state.select(Some(prev));
+    }
+
+    /// Moves from the action picker to the confirm dialog
+    pub fn action_picker_confirm(&mut self) {
+        if let Some(i) = self.action_picker_state.selected()
+            && let Some(unit) = self.selected_service().map(|s| s.unit.clone())
+        {
+            self.pending_action = Some((unit, ServiceAction::ALL[i]));
+        }
+        self.show_action_picker = false;
+    }
+
+    pub fn cancel_action(&mut self) {
+        self.pending_action = None;
+    }
+
+    /// Starts the confirmed action on the unit it was confirmed for; see `apply_action`
+    pub fn confirm_action(&mut self) {
+        let Some((unit, action)) = self.pending_action.take() else {
+            return;
+        };
+        self.jobs_running += 1;
+        self.loader.run_action(self.backend.clone(), unit, action);
+    }
+
+    /// Reports how an action went and refreshes the unit's row
+    fn apply_action(&mut self, unit: &str, action: ServiceAction, result: Result<String, String>) {
+        self.jobs_running = self.jobs_running.saturating_sub(1);
+        self.message = Some(match result {
+            Ok(output) => StatusMessage {
+                text: if output.is_empty() {
+                    format!("{}: {} succeeded", unit, action.verb())
+                } else {
+                    format!("{}: {}", unit, output)
+                },
+                is_error: false,
+            },
+            Err(e) => StatusMessage {
+                text: format!("{}: {}", unit, e),
+                is_error: true,
+            },
+        });
+
+        self.refresh_service(unit);
+    }
+
+    pub fn open_timers(&mut self) {
+        if !self.require_live("Timers") {
+            return;
+        }
+        self.view = View::Timers;
+        self.load_timers();
+    }
+
+    pub fn close_timers(&mut self) {
+        self.view = View::Services;
+    }
+
+    /// Starts re-reading the timers in the background
+    pub fn load_timers(&mut self) {
+        self.timers_loading = true;
+        self.loader.load_timers(self.backend.clone());
+    }
+
+    fn apply_timers(&mut self, result: Result<Vec<SystemdTimer>, String>) {
+        self.timers_loading = false;
+        match result {
+            Ok(timers) => {
+                self.timers = timers;
+                self.timers_error = None;
+                match self.timers_state.selected() {
+                    _ if self.timers.is_empty() => self.timers_state.select(None),
+                    Some(i) if i < self.timers.len() => {}
+                    _ => self.timers_state.select(Some(0)),
+                }
+            }
+            Err(e) => {
+                self.timers_error = Some(e);
+            }
+        }
+    }
+
+    pub fn timers_next(&mut self) {
+        if self.timers.is_empty() {
+            return;
+        }
+        let i = match self.timers_state.selected() {
+            Some(i) if i + 1 < self.timers.len() => i + 1,
+            _ => 0,
+        };
+        self.timers_state.select(Some(i));
+    }
+
+    pub fn timers_previous(&mut self) {
+        if self.timers.is_empty() {
+            return;
+        }
+        let i = match self.timers_state.selected() {
+            Some(0) | None => self.timers.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.timers_state.select(Some(i));
+    }
+
+    pub fn timers_go_to_top(&mut self) {
+        if !self.timers.is_empty() {
+            self.timers_state.select(Some(0));
+        }
+    }
+
+    pub fn timers_go_to_bottom(&mut self) {
+        if !self.timers.is_empty() {
+            self.timers_state.select(Some(self.timers.len() - 1));
+        }
+    }
+
+    pub fn selected_timer(&self) -> Option<&SystemdTimer> {
+        self.timers_state.selected().and_then(|i| self.timers.get(i))
+    }
+
+    /// Leaves the timers view and opens the logs of the unit the selected timer activates
+    pub fn open_timer_target_logs(&mut self) {
+        let Some(unit) = self.selected_timer().map(|t| t.activates.clone()) else {
+            return;
+        };
+        if self.focus_unit(&unit) {
+            self.show_logs = true;
+        }
+    }
+
+    /// Selects a unit in the main list, switching unit type and clearing filters as needed.
+    /// Returns false if the unit is known not to exist; when the list has to be
+    /// reloaded first, the selection happens once it arrives.
+    pub fn focus_unit(&mut self, unit: &str) -> bool {
+        self.view = View::Services;
+        self.search_query.clear();
+        self.status_filter = None;
+
+        let unit_type = unit.rsplit_once('.').map(|(_, t)| t.to_string());
+        let type_matches = self.unit_type.is_none() || self.unit_type == unit_type;
+        if !type_matches {
+            self.unit_type = unit_type;
+        }
+        if !type_matches || !self.services.iter().any(|s| s.unit == unit) {
+            self.pending_focus = Some(unit.to_string());
+            self.load_services();
+            return true;
+        }
+
+        self.update_filter();
+        self.select_unit(unit)
+    }
+
+    fn select_unit(&mut self, unit: &str) -> bool {
+        match self
+            .filtered_indices
+            .iter()
+            .position(|&i| self.services[i].unit == unit)
+        {
+            Some(pos) => {
+                self.list_state.select(Some(pos));
+                true
+            }
+            None => {
+                self.message = Some(StatusMessage {
+                    text: format!(
+                        "{} is not known to the {} manager",
+                        unit,
+                        self.backend.scope().label()
+                    ),
+                    is_error: true,
+                });
+                false
+            }
+        }
+    }
+
+    /// Asks the main loop to suspend the UI and edit the selected unit's override
+    pub fn request_edit(&mut self) {
+        if !self.require_live("Editing") {
+            return;
+        }
+        self.pending_edit = self.selected_service().map(|s| s.unit.clone());
+    }
+
+    /// Validates and installs an edited override, leaving the old file alone on any error
+    pub fn finish_edit(
+        &mut self,
+        unit: &str,
+        original: Option<&str>,
+        edited: Result<String, String>,
+    ) {
+        let content = match edited {
+            Ok(content) => content,
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: format!("{}: override not saved, {}", unit, e),
+                    is_error: true,
+                });
+                return;
+            }
+        };
+
+        let unchanged = match original {
+            Some(original) => original == content,
+            None => dropin::is_empty(&content),
+        };
+        if unchanged {
+            self.message = Some(StatusMessage {
+                text: format!("{}: override unchanged", unit),
+                is_error: false,
+            });
+            return;
+        }
+
+        if let Err(e) = dropin::validate(&content) {
+            self.message = Some(StatusMessage {
+                text: format!("{}: override not saved, {}", unit, e),
+                is_error: true,
+            });
+            return;
+        }
+
+        let path = match dropin::install(self.backend.scope(), unit, &content) {
+            Ok(path) => path,
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: format!("{}: failed to write override: {}", unit, e),
+                    is_error: true,
+                });
+                return;
+            }
+        };
+
+        self.jobs_running += 1;
+        self.loader.daemon_reload(self.backend.clone(), unit.to_string(), path);
+    }
+
+    /// Reports a saved override once systemd reloaded, and shows the new state of the unit
+    fn apply_reload(&mut self, unit: &str, path: &Path, result: Result<(), String>) {
+        self.jobs_running = self.jobs_running.saturating_sub(1);
+        self.refresh_service(unit);
+        if self.show_logs && self.panel_mode == PanelMode::UnitFile {
+            self.open_unit_file();
+        }
+
+        self.message = Some(match result {
+            Ok(()) => StatusMessage {
+                text: format!("{}: saved {} and reloaded", unit, path.display()),
+                is_error: false,
+            },
+            Err(e) => StatusMessage {
+                text: format!("{}: saved {}, but {}", unit, path.display(), e),
+                is_error: true,
+            },
+        });
+    }
+
+    /// Re-reads a single unit in the background, then updates it in place
+    pub fn refresh_service(&mut self, unit: &str) {
+        self.loader.load_service(self.backend.clone(), unit.to_string());
+    }
+
+    /// Updates a re-read unit in place, keeping the selection where it is
+    fn apply_service(&mut self, unit: &str, result: Result<Option<SystemdService>, String>) {
+        let selected = self.selected_service().map(|s| s.unit.clone());
+        match result {
+            Ok(Some(updated)) => {
+                if let Some(service) = self.services.iter_mut().find(|s| s.unit == unit) {
+                    *service = updated;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: e,
+                    is_error: true,
+                });
+            }
+        }
+
+        self.update_filter();
+        if let Some(ref selected) = selected {
+            self.reselect(selected);
+        }
+        // Force a logs reload so the action's journal entries show up
+        if selected.as_deref() == Some(unit) {
+            self.last_selected_service = None;
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.filtered_indices.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i >= self.filtered_indices.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        if self.filtered_indices.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.filtered_indices.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn go_to_top(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    pub fn go_to_bottom(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.list_state.select(Some(self.filtered_indices.len() - 1));
+        }
+    }
+
+    pub fn selected_service(&self) -> Option<&SystemdService> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.filtered_indices.get(i))
+            .map(|&i| &self.services[i])
+    }
+
+    /// Starts loading the logs of the selected unit if the selection changed
+    pub fn load_logs_for_selected(&mut self) {
+        let current_service = self.selected_service().map(|s| s.unit.clone());
+
+        if current_service != self.last_selected_service {
+            self.last_selected_service = current_service.clone();
+            self.logs_scroll = 0;
+            self.logs_has_older = false;
+            self.logs_has_newer = false;
+            self.logs_paging = false;
+            self.clear_log_search();
+            self.logs.clear();
+            self.entry_detail = None;
+
+            if let Some(unit) = current_service {
+                if self.follower.is_some() {
+                    self.start_follow(&unit);
+                }
+                self.logs_loading = true;
+                self.loader.load_logs(self.backend.clone(), self.log_query(&unit));
+            } else {
+                self.follower = None;
+                self.logs_loading = false;
+                self.loader.discard_logs();
+            }
+            self.rebuild_log_lines();
+        }
+    }
+
+    fn apply_logs(&mut self, result: Result<Vec<JournalEntry>, String>) {
+        self.logs_loading = false;
+        self.entry_detail = None;
+        match result {
+            Ok(logs) => {
+                self.logs_has_older = logs.len() == self.log_page_size;
+                self.logs = logs;
+                // Auto-scroll to bottom (most recent logs)
+                if !self.logs.is_empty() {
+                    self.logs_scroll = self.logs.len().saturating_sub(1);
+                }
+            }
+            Err(e) => {
+                self.logs = vec![JournalEntry::from_message(format!(
+                    "Error fetching logs: {}",
+                    e
+                ))];
+            }
+        }
+        self.rebuild_log_lines();
+    }
+
+    fn log_query(&self, unit: &str) -> LogQuery {
+        LogQuery {
+            source: self.backend.journal_source(),
+            scope: self.backend.scope(),
+            unit: unit.to_string(),
+            lines: self.log_page_size,
+            priority: self.log_priority,
+            since: self.log_since.clone(),
+            until: self.log_until.clone(),
+            boot: self.log_boot,
+        }
+    }
+
+    /// Starts fetching the page before the first loaded entry
+    fn load_older_logs(&mut self) {
+        if self.panel_mode != PanelMode::Logs || !self.logs_has_older {
+            return;
+        }
+        self.request_page(PageDirection::Older);
+    }
+
+    /// Starts fetching the page after the last loaded entry
+    fn load_newer_logs(&mut self) {
+        if self.panel_mode != PanelMode::Logs || !self.logs_has_newer {
+            return;
+        }
+        self.request_page(PageDirection::Newer);
+    }
+
+    fn request_page(&mut self, direction: PageDirection) {
+        if self.logs_loading || self.logs_paging {
+            return;
+        }
+        let Some(unit) = self.last_selected_service.clone() else {
+            return;
+        };
+        let edge = match direction {
+            PageDirection::Older => self.logs.first(),
+            PageDirection::Newer => self.logs.last(),
+        };
+        let Some(cursor) = edge.and_then(|entry| entry.cursor.clone()) else {
+            return;
+        };
+        self.logs_paging = true;
+        self.loader
+            .load_log_page(self.backend.clone(), self.log_query(&unit), cursor, direction);
+    }
+
+    /// Adds a fetched page if the entries it continues are still at that edge
+    fn apply_log_page(
+        &mut self,
+        cursor: &str,
+        direction: PageDirection,
+        result: Result<Vec<JournalEntry>, String>,
+    ) {
+        self.logs_paging = false;
+        let page = match result {
+            Ok(page) => page,
+            Err(e) => {
+                self.message = Some(StatusMessage {
+                    text: format!("Error fetching logs: {}", e),
+                    is_error: true,
+                });
+                return;
+            }
+        };
+        let edge = match direction {
+            PageDirection::Older => self.logs.first(),
+            PageDirection::Newer => self.logs.last(),
+        };
+        if edge.and_then(|entry| entry.cursor.as_deref()) != Some(cursor) {
+            return;
+        }
+        match direction {
+            PageDirection::Older => self.prepend_logs(page),
+            PageDirection::Newer => self.append_logs(page),
+        }
+    }
+
+    /// Inserts an older page at the front, keeping the same lines in view and
+    /// dropping entries from the back beyond `MAX_LOG_ENTRIES`
+    fn prepend_logs(&mut self, page: Vec<JournalEntry>) {
+        self.logs_has_older = page.len() == self.log_page_size;
+        let added = page.len();
+        self.logs.splice(0..0, page);
+        self.logs_scroll += added;
+
+        let excess = self.logs.len().saturating_sub(MAX_LOG_ENTRIES);
+        if excess > 0 {
+            self.logs.truncate(MAX_LOG_ENTRIES);
+            self.logs_has_newer = true;
+            // A page as large as the limit can push the view past the entries kept
+            self.logs_scroll = self.logs_scroll.min(self.logs.len().saturating_sub(1));
+        }
+        self.entry_detail = self
+            .entry_detail
+            .map(|i| i + added)
+            .filter(|&i| i < self.logs.len());
+
+        let match_index = self.log_search_match_index;
+        self.rebuild_log_lines();
+        if let Some(i) = match_index {
+            let shifted = self.log_search_matches.iter().take_while(|&&l| l < added).count();
+            self.log_search_match_index = Some(i + shifted)
+                .filter(|&i| i < self.log_search_matches.len());
+        }
+    }
+
+    /// Appends a newer page, evicting the oldest entries if needed
+    fn append_logs(&mut self, page: Vec<JournalEntry>) {
+        self.logs_has_newer = page.len() == self.log_page_size;
+        let query = self.log_search_query.to_lowercase();
+        for entry in page {
+            self.push_log_entry(entry, &query);
+        }
+        self.evict_oldest_logs();
+    }
+
+    fn push_log_entry(&mut self, entry: JournalEntry, lowercase_query: &str) {
+        let line = entry.format(&self.log_columns);
+        if !lowercase_query.is_empty() && line.to_lowercase().contains(lowercase_query) {
+            self.log_search_matches.push(self.log_lines.len());
+        }
+        self.log_lines.push(line);
+        self.logs.push(entry);
+    }
+
+    /// Drops entries from the front beyond `MAX_LOG_ENTRIES`, keeping the view in place
+    fn evict_oldest_logs(&mut self) {
+        let excess = self.logs.len().saturating_sub(MAX_LOG_ENTRIES);
+        if excess == 0 {
+            return;
+        }
+        self.logs.drain(..excess);
+        self.log_lines.drain(..excess);
+        self.logs_has_older = true;
+        self.logs_scroll = self.logs_scroll.saturating_sub(excess);
+        self.entry_detail = self.entry_detail.and_then(|i| i.checked_sub(excess));
+
+        let dropped = self.log_search_matches.iter().take_while(|&&l| l < excess).count();
+        self.log_search_matches.drain(..dropped);
+        for line in &mut self.log_search_matches {
+            *line -= excess;
+        }
+        self.log_search_match_index = self
+            .log_search_match_index
+            .and_then(|i| i.checked_sub(dropped))
+            .filter(|&i| i < self.log_search_matches.len());
+    }
+
+    /// Whether the follower delivered an entry a newer page already loaded
+    fn is_loaded(&self, entry: &JournalEntry) -> bool {
+        entry.cursor.is_some()
+            && self
+                .logs
+                .iter()
+                .rev()
+                .take_while(|e| e.realtime_usec >= entry.realtime_usec)
+                .any(|e| e.cursor == entry.cursor)
+    }
+
+    fn rebuild_log_lines(&mut self) {
+        self.log_lines = self
+            .logs
+            .iter()
+            .map(|entry| entry.format(&self.log_columns))
+            .collect();
+        if !self.log_search_query.is_empty() {
+            let (scroll, index) = (self.logs_scroll, self.log_search_match_index);
+            self.update_log_search();
+            self.logs_scroll = scroll;
+            self.log_search_match_index = index.filter(|&i| i < self.log_search_matches.len());
+        }
+    }
+
+    /// Turns live follow mode on or off for the unit shown in the logs panel
+    pub fn toggle_follow(&mut self) {
+        if self.follower.is_some() {
+            self.follower = None;
+            return;
+        }
+        if let Some(unit) = self.last_selected_service.clone() {
+            self.start_follow(&unit);
+        }
+    }
+
+    fn start_follow(&mut self, unit: &str) {
+        if !self.require_journal("Following") {
+            return;
+        }
+        self.follow_paused = false;
+        // Continue from the newest loaded entry so nothing written since it is missed
+        let after = self
+            .logs
+            .last()
+            .filter(|_| !self.logs_loading && !self.logs_has_newer)
+            .and_then(|entry| entry.cursor.clone());
+        match self.backend.follow_logs(&self.log_query(unit), after.as_deref()) {
+            Ok(follower) => self.follower = Some(follower),
+            Err(e) => {
+                self.follower = None;
+                self.message = Some(StatusMessage {
+                    text: e,
+                    is_error: true,
+                });
+            }
+        }
+    }
+
+    /// Appends entries received from the follower
+    fn poll_follower(&mut self) {
+        let Some(ref follower) = self.follower else {
+            return;
+        };
+        // Entries wait in the channel until the initial load has arrived
+        if self.logs_loading {
+            return;
+        }
+        let entries = follower.drain();
+        // Newer entries were evicted; paging forward will read these from the journal
+        if entries.is_empty() || self.logs_has_newer {
+            return;
+        }
+
+        let query = self.log_search_query.to_lowercase();
+        for entry in entries {
+            if !self.is_loaded(&entry) {
+                self.push_log_entry(entry, &query);
+            }
+        }
+        self.evict_oldest_logs();
+    }
+
+    /// Whether the logs panel should stay pinned to the newest entry
+    pub fn follow_pinned(&self) -> bool {
+        self.follower.is_some() && !self.follow_paused
+    }
+
+    /// Scrolls the logs panel so its last line is at the bottom while following
+    fn pin_follow(&mut self) {
+        if self.follow_pinned() && self.panel_mode == PanelMode::Logs {
+            self.logs_scroll = self
+                .panel_lines()
+                .len()
+                .saturating_sub(self.logs_visible_lines);
+        }
+    }
+
+    /// Opens the side panel on the `systemctl cat` output of the selected unit
+    pub fn open_unit_file(&mut self) {
+        let Some(unit) = self.selected_service().map(|s| s.unit.clone()) else {
+            return;
+        };
+        if !self.require_live("Unit files") {
+            return;
+        }
+
+        self.unit_file.clear();
+        self.panel_loading = true;
+        self.loader.load_unit_file(self.backend.clone(), unit);
+        self.show_panel(PanelMode::UnitFile);
+    }
+
+    /// Opens the side panel on the grouped `systemctl show` properties of the selected unit
+    pub fn open_properties(&mut self) {
+        let Some(unit) = self.selected_service().map(|s| s.unit.clone()) else {
+            return;
+        };
+
+        self.properties_lines.clear();
+        self.properties = None;
+        self.panel_loading = true;
+        self.loader.load_properties(self.backend.clone(), unit);
+        self.show_panel(PanelMode::Properties);
+    }
+
+    /// Whether features reading the running system are available, reporting why not
+    fn require_live(&mut self, feature: &str) -> bool {
+        if !self.backend.read_only() {
+            return true;
+        }
+        self.message = Some(StatusMessage {
+            text: format!("{} unavailable in a read-only {}", feature, self.backend.label()),
+            is_error: true,
+        });
+        false
+    }
+
+    /// Whether there is a journal to read; a snapshot only has one if given alongside it
+    fn require_journal(&mut self, feature: &str) -> bool {
+        if !self.backend.read_only() || self.backend.journal_source() != JournalSource::Local {
+            return true;
+        }
+        self.message = Some(StatusMessage {
+            text: format!("{} unavailable: no journal was given with the snapshot", feature),
+            is_error: true,
+        });
+        false
+    }
+
+    fn show_panel(&mut self, mode: PanelMode) {
+        self.follower = None;
+        self.panel_mode = mode;
+        self.show_logs = true;
+        self.logs_scroll = 0;
+        self.clear_log_search();
+    }
+
+    /// Lines currently shown in the side panel
+    pub fn panel_lines(&self) -> &[String] {
+        match self.panel_mode {
+            PanelMode::Logs => &self.log_lines,
+            PanelMode::UnitFile => &self.unit_file,
+            PanelMode::Properties => &self.properties_lines,
+        }
+    }
+
+    /// The panel line a copy acts on: the current search match, else the top visible line
+    pub fn current_panel_line(&self) -> Option<usize> {
+        self.log_search_match_index
+            .and_then(|i| self.log_search_matches.get(i).copied())
+            .or(Some(self.logs_scroll))
+            .filter(|&i| i < self.panel_lines().len())
+    }
+
+    pub fn copy_current_line(&mut self) {
+        if let Some(line) = self.current_panel_line().map(|i| self.panel_lines()[i].clone()) {
+            self.message = Some(StatusMessage {
+                text: "Copied line to clipboard".to_string(),
+                is_error: false,
+            });
+            self.pending_copy = Some(line);
+        }
+    }
+
+    pub fn copy_panel(&mut self) {
+        let lines = self.panel_lines();
+        if lines.is_empty() {
+            return;
+        }
+        let (count, text) = (lines.len(), lines.join("\n"));
+        self.message = Some(StatusMessage {
+            text: format!("Copied {} lines to clipboard", count),
+            is_error: false,
+        });
+        self.pending_copy = Some(text);
+    }
+
+    pub fn scroll_logs_up(&mut self, amount: usize) {
+        if amount > self.logs_scroll {
+            self.load_older_logs();
+        }
+        self.logs_scroll = self.logs_scroll.saturating_sub(amount);
+        self.follow_paused = true;
+    }
+
+    pub fn scroll_logs_down(&mut self, amount: usize, visible_lines: usize) {
+        if self.logs_scroll + amount + visible_lines > self.panel_lines().len() {
+            self.load_newer_logs();
+        }
+        let len = self.panel_lines().len();
+        if len > 0 {
+            let max_scroll = len.saturating_sub(visible_lines);
+            self.logs_scroll = (self.logs_scroll + amount).min(max_scroll);
+            // Reaching the bottom again resumes following
+            if self.logs_scroll == max_scroll && !self.logs_has_newer {
+                self.follow_paused = false;
+            }
+        }
+    }
+
+    /// Selects `unit` and opens the logs panel on it
+    pub fn open_logs(&mut self, unit: &str) {
+        self.focus_unit(unit);
+        self.show_logs = true;
+        self.panel_mode = PanelMode::Logs;
+    }
+
+    pub fn toggle_logs(&mut self) {
+        self.show_logs = !self.show_logs;
+        self.follower = None;
+        if self.panel_mode != PanelMode::Logs {
+            // Leaving the unit file or properties view; logs_scroll belonged to it
+            self.panel_mode = PanelMode::Logs;
+            self.last_selected_service = None;
+        }
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    pub fn page_up(&mut self, page_size: usize) {
+        if self.filtered_indices.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        let new_index = current.saturating_sub(page_size);
+        self.list_state.select(Some(new_index));
+    }
+
+    pub fn page_down(&mut self, page_size: usize) {
+        if self.filtered_indices.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        let max_index = self.filtered_indices.len().saturating_sub(1);
+        let new_index = (current + page_size).min(max_index);
+        self.list_state.select(Some(new_index));
+    }
+
+    pub fn update_log_search(&mut self) {
+        self.log_search_matches.clear();
+        self.log_search_match_index = None;
+
+        if self.log_search_query.is_empty() {
+            return;
+        }
+
+        let query = self.log_search_query.to_lowercase();
+        self.log_search_matches = self
+            .panel_lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Auto-scroll to first match
+        if !self.log_search_matches.is_empty() {
+            self.follow_paused = true;
+            self.log_search_match_index = Some(0);
+            self.logs_scroll = self.log_search_matches[0];
+        }
+    }
+
+    pub fn clear_log_search(&mut self) {
+        self.log_search_query.clear();
+        self.log_search_matches.clear();
+        self.log_search_match_index = None;
+    }
+
+    pub fn next_log_match(&mut self, visible_lines: usize) {
+        if self.log_search_matches.is_empty() {
+            return;
+        }
+        let next = match self.log_search_match_index {
+            Some(i) => (i + 1) % self.log_search_matches.len(),
+            None => 0,
+        };
+        self.log_search_match_index = Some(next);
+        self.follow_paused = true;
+        let line_idx = self.log_search_matches[next];
+        // Scroll so match is visible
+        if line_idx < self.logs_scroll || line_idx >= self.logs_scroll + visible_lines {
+            self.logs_scroll = line_idx;
+        }
+    }
+
+    pub fn prev_log_match(&mut self, visible_lines: usize) {
+        if self.log_search_matches.is_empty() {
+            return;
+        }
+        let prev = match self.log_search_match_index {
+            Some(0) => self.log_search_matches.len() - 1,
+            Some(i) => i - 1,
+            None => self.log_search_matches.len() - 1,
+        };
+        self.log_search_match_index = Some(prev);
+        self.follow_paused = true;
+        let line_idx = self.log_search_matches[prev];
+        if line_idx < self.logs_scroll || line_idx >= self.logs_scroll + visible_lines {
+            self.logs_scroll = line_idx;
+        }
+    }
+
+    pub fn logs_go_to_top(&mut self) {
+        self.logs_scroll = 0;
+        self.follow_paused = true;
+    }
+
+    pub fn logs_go_to_bottom(&mut self, visible_lines: usize) {
+        if self.logs_has_newer {
+            // Newer entries were evicted; start over from the end of the journal
+            self.reload_logs();
+            self.follow_paused = false;
+            return;
+        }
+        let len = self.panel_lines().len();
+        if len > 0 {
+            self.logs_scroll = len.saturating_sub(visible_lines);
+        }
+        self.follow_paused = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{loaded_app, sample, settle, FakeBackend};
+
+    fn app() -> App<FakeBackend> {
+        loaded_app(sample())
+    }
+
+    fn selected(app: &App<FakeBackend>) -> &str {
+        app.selected_service().map_or("", |s| s.unit.as_str())
+    }
+
+    fn visible(app: &App<FakeBackend>) -> Vec<&str> {
+        app.filtered_indices
+            .iter()
+            .map(|&i| app.services[i].unit.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn loads_services_of_the_default_type() {
+        let app = app();
+        assert_eq!(visible(&app), ["cron.service", "nginx.service", "ssh.service"]);
+        assert_eq!(selected(&app), "cron.service");
+        assert!(app.error.is_none());
+    }
+
+    #[test]
+    fn navigation_wraps_around() {
+        let mut app = app();
+        app.previous();
+        assert_eq!(selected(&app), "ssh.service");
+        app.next();
+        assert_eq!(selected(&app), "cron.service");
+        app.go_to_bottom();
+        assert_eq!(selected(&app), "ssh.service");
+        app.go_to_top();
+        app.page_down(10);
+        assert_eq!(selected(&app), "ssh.service");
+        app.page_up(1);
+        assert_eq!(selected(&app), "nginx.service");
+    }
+
+    #[test]
+    fn search_matches_unit_name_and_description() {
+        let mut app = app();
+        app.search_query = "web server".to_string();
+        app.update_filter();
+        assert_eq!(visible(&app), ["nginx.service"]);
+
+        app.search_query = "SSH".to_string();
+        app.update_filter();
+        assert_eq!(visible(&app), ["ssh.service"]);
+        assert_eq!(selected(&app), "ssh.service");
+
+        app.clear_search();
+        assert_eq!(visible(&app).len(), 3);
+    }
+
+    #[test]
+    fn status_filter_keeps_matching_sub_states() {
+        let mut app = app();
+        app.open_status_picker();
+        let failed = STATUS_OPTIONS.iter().position(|&s| s == "failed").unwrap();
+        app.status_picker_state.select(Some(failed));
+        app.status_picker_confirm();
+        assert_eq!(visible(&app), ["nginx.service"]);
+
+        app.search_query = "cron".to_string();
+        app.update_filter();
+        assert!(visible(&app).is_empty());
+        assert!(app.selected_service().is_none());
+    }
+
+    #[test]
+    fn type_picker_reloads_with_the_chosen_type() {
+        let mut app = app();
+        app.open_type_picker();
+        let timer = UNIT_TYPE_OPTIONS.iter().position(|&t| t == "timer").unwrap();
+        app.type_picker_state.select(Some(timer));
+        app.type_picker_confirm();
+        settle(&mut app);
+        assert_eq!(visible(&app), ["logrotate.timer"]);
+        assert_eq!(app.unit_type_title(), "Timers");
+
+        app.open_type_picker();
+        app.type_picker_state.select(Some(0));
+        app.type_picker_confirm();
+        settle(&mut app);
+        assert_eq!(visible(&app).len(), 5);
+    }
+
+    #[test]
+    fn confirmed_action_runs_and_refreshes_the_row() {
+        let mut app = app();
+        app.next();
+        assert_eq!(selected(&app), "nginx.service");
+
+        app.open_action_picker();
+        let restart = ServiceAction::ALL
+            .iter()
+            .position(|&a| a == ServiceAction::Restart)
+            .unwrap();
+        app.action_picker_state.select(Some(restart));
+        app.action_picker_confirm();
+        assert_eq!(
+            app.pending_action,
+            Some(("nginx.service".to_string(), ServiceAction::Restart))
+        );
+
+        app.confirm_action();
+        assert_eq!(app.jobs_running, 1);
+        settle(&mut app);
+        assert_eq!(app.jobs_running, 0);
+        assert_eq!(
+            app.backend.actions(),
+            [("nginx.service".to_string(), ServiceAction::Restart)]
+        );
+        let service = app.selected_service().unwrap();
+        assert_eq!((service.active.as_str(), service.sub.as_str()), ("active", "running"));
+        assert!(!app.message.as_ref().unwrap().is_error);
+    }
+
+    #[test]
+    fn refresh_during_confirm_keeps_the_confirmed_unit() {
+        let mut app = app();
+        app.status_filter = Some("running".to_string());
+        app.update_filter();
+        app.go_to_bottom();
+        assert_eq!(selected(&app), "ssh.service");
+
+        app.open_action_picker();
+        let stop = ServiceAction::ALL.iter().position(|&a| a == ServiceAction::Stop).unwrap();
+        app.action_picker_state.select(Some(stop));
+        app.action_picker_confirm();
+
+        // ssh stops elsewhere and drops out of the filter while the dialog is open
+        app.backend.set_state("ssh.service", "inactive", "dead");
+        app.load_services();
+        settle(&mut app);
+        assert_eq!(selected(&app), "cron.service");
+
+        app.confirm_action();
+        settle(&mut app);
+        assert_eq!(app.backend.actions(), [("ssh.service".to_string(), ServiceAction::Stop)]);
+    }
+
+    #[test]
+    fn refreshed_unit_does_not_move_the_selection() {
+        let mut app = app();
+        app.toggle_logs();
+        settle(&mut app);
+        app.refresh_service("nginx.service");
+        app.go_to_bottom();
+        settle(&mut app);
+        assert_eq!(selected(&app), "ssh.service");
+        assert_eq!(app.last_selected_service.as_deref(), Some("ssh.service"));
+    }
+
+    #[test]
+    fn dependencies_expand_once_fetched() {
+        let mut app = app();
+        app.open_deps();
+        assert!(app.deps_loading);
+        assert!(!app.deps.as_ref().unwrap().nodes[0].expanded);
+        settle(&mut app);
+        assert!(!app.deps_loading);
+        assert!(app.deps.as_ref().unwrap().nodes[0].expanded);
+
+        // Closing the tree drops a fetch that is still running
+        app.deps_cycle_direction();
+        app.close_deps();
+        settle(&mut app);
+        assert!(app.deps.is_none());
+    }
+
+    #[test]
+    fn scope_switch_connects_in_the_background() {
+        let mut app = app();
+        app.toggle_scope();
+        assert!(app.services.is_empty());
+        assert!(app.services_loading);
+        settle(&mut app);
+        assert_eq!(app.backend.scope(), Scope::User);
+        assert_eq!(visible(&app), ["cron.service", "nginx.service", "ssh.service"]);
+    }
+
+    #[test]
+    fn cancelled_action_does_nothing() {
+        let mut app = app();
+        app.open_action_picker();
+        app.action_picker_confirm();
+        app.cancel_action();
+        app.confirm_action();
+        assert!(app.backend.actions().is_empty());
+    }
+
+    #[test]
+    fn logs_load_for_the_selected_unit() {
+        let mut app = app();
+        app.toggle_logs();
+        settle(&mut app);
+        assert_eq!(app.last_selected_service.as_deref(), Some("cron.service"));
+        assert_eq!(app.logs.len(), 3);
+        assert!(app.log_lines[2].ends_with("job failed"));
+        assert_eq!(app.logs_scroll, 2);
+
+        app.log_search_query = "job".to_string();
+        app.update_log_search();
+        assert_eq!(app.log_search_matches, [1, 2]);
+
+        // A unit without entries shows an empty panel
+        app.next();
+        settle(&mut app);
+        assert!(app.logs.is_empty());
+        assert!(app.log_search_query.is_empty());
+    }
+
+    #[test]
+    fn scrolling_past_the_top_loads_older_entries() {
+        let mut app = app();
+        app.log_page_size = 2;
+        app.toggle_logs();
+        settle(&mut app);
+        assert_eq!(app.logs.len(), 2);
+        assert!(app.logs_has_older);
+
+        app.scroll_logs_up(5);
+        assert!(app.logs_paging);
+        // Scrolling on while the page loads does not request it twice
+        app.scroll_logs_up(5);
+        settle(&mut app);
+        assert_eq!(app.logs.len(), 3);
+        assert_eq!(app.logs[0].message, "started");
+        assert!(!app.logs_has_older);
+        // The page arrived after the scroll, so the view stays on the same entry
+        assert_eq!(app.logs_scroll, 1);
+    }
+
+    #[test]
+    fn follow_appends_new_entries() {
+        let mut app = app();
+        app.toggle_logs();
+        settle(&mut app);
+        app.toggle_follow();
+        assert!(app.follow_pinned());
+
+        app.logs_visible_lines = 2;
+        app.backend.log("cron.service", "job ran again");
+        app.backend.log("ssh.service", "accepted key");
+        app.tick();
+        assert_eq!(app.logs.len(), 4);
+        assert_eq!(app.logs[3].message, "job ran again");
+        // Pinned so the newest lines fill the panel
+        assert_eq!(app.logs_scroll, 2);
+
+        app.scroll_logs_up(1);
+        app.backend.log("cron.service", "job ran once more");
+        app.tick();
+        assert_eq!(app.logs.len(), 5);
+        assert_eq!(app.logs_scroll, 1);
+    }
+
+    #[test]
+    fn follow_starts_after_the_newest_loaded_entry() {
+        let mut app = app();
+        app.toggle_logs();
+        settle(&mut app);
+
+        // Written after the load but before following started
+        app.backend.log("cron.service", "job ran late");
+        app.toggle_follow();
+        app.tick();
+        let messages: Vec<&str> = app.logs.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["started", "job ran", "job failed", "job ran late"]);
+    }
+
+    #[test]
+    fn refresh_marks_changed_units_until_acknowledged() {
+        let mut app = app();
+        app.backend.set_state("ssh.service", "inactive", "dead");
+        app.load_services();
+        settle(&mut app);
+        assert_eq!(app.changes.len(), 1);
+        assert_eq!(app.changes["ssh.service"].from, "active/running");
+
+        // Flapping back keeps the original state and counts the change
+        app.backend.set_state("ssh.service", "active", "running");
+        app.load_services();
+        settle(&mut app);
+        assert_eq!(app.changes["ssh.service"].count, 2);
+
+        app.go_to_bottom();
+        app.acknowledge_change();
+        assert!(app.changes.is_empty());
+    }
+
+    #[test]
+    fn focus_unit_switches_type_and_clears_filters() {
+        let mut app = app();
+        app.search_query = "cron".to_string();
+        app.update_filter();
+        assert!(app.focus_unit("tmp.mount"));
+        settle(&mut app);
+        assert_eq!(app.unit_type.as_deref(), Some("mount"));
+        assert_eq!(selected(&app), "tmp.mount");
+        assert!(app.search_query.is_empty());
+
+        // Unknown units are reported once the reloaded list has arrived
+        app.focus_unit("gone.mount");
+        settle(&mut app);
+        assert!(app.message.as_ref().unwrap().is_error);
+        assert_eq!(selected(&app), "tmp.mount");
+    }
+
+    /// Journal entries numbered `range`, each with a cursor and fields
+    fn journal(range: std::ops::Range<usize>) -> Vec<JournalEntry> {
+        range
+            .map(|i| {
+                let mut entry = JournalEntry::from_message(format!("entry {}.", i));
+                entry.cursor = Some(format!("s=0;i={}", i));
+                entry.fields.insert("MESSAGE".to_string(), entry.message.clone());
+                entry
+            })
+            .collect()
+    }
+
+    fn logs_app(page_size: usize, entries: Vec<JournalEntry>) -> App<FakeBackend> {
+        let mut app = app();
+        app.toggle_logs();
+        settle(&mut app);
+        app.log_page_size = page_size;
+        app.apply_logs(Ok(entries));
+        app
+    }
+
+    fn detail_message(app: &App<FakeBackend>) -> Option<&str> {
+        app.entry_detail.map(|i| app.logs[i].message.as_str())
+    }
+
+    #[test]
+    fn full_page_means_more_history() {
+        let app = logs_app(5, journal(20..25));
+        assert!(app.logs_has_older);
+        assert!(!app.logs_has_newer);
+        assert_eq!(app.logs_scroll, 4);
+
+        let app = logs_app(5, journal(20..23));
+        assert!(!app.logs_has_older);
+    }
+
+    #[test]
+    fn older_page_keeps_view_search_and_detail_in_place() {
+        let mut app = logs_app(5, journal(20..25));
+        app.logs_scroll = 3;
+        app.open_entry_detail();
+        assert_eq!(detail_message(&app), Some("entry 23."));
+        app.log_search_query = "1".to_string();
+        app.update_log_search();
+        assert_eq!(app.log_search_matches, [1]);
+
+        app.prepend_logs(journal(17..20));
+        assert!(!app.logs_has_older);
+        assert_eq!(app.logs.len(), 8);
+        assert_eq!(app.logs_scroll, 4);
+        assert_eq!(detail_message(&app), Some("entry 23."));
+        assert_eq!(app.log_search_matches, [0, 1, 2, 4]);
+        assert_eq!(app.log_search_match_index, Some(3));
+        assert_eq!(app.current_panel_line(), Some(4));
+    }
+
+    #[test]
+    fn older_page_past_the_limit_drops_the_newest_entries() {
+        let mut app = logs_app(5, journal(5..MAX_LOG_ENTRIES + 5));
+        app.open_entry_detail();
+        assert_eq!(app.entry_detail, Some(MAX_LOG_ENTRIES - 1));
+
+        app.prepend_logs(journal(0..5));
+        assert!(app.logs_has_older);
+        assert!(app.logs_has_newer);
+        assert_eq!(app.logs.len(), MAX_LOG_ENTRIES);
+        assert_eq!(app.logs[0].message, "entry 0.");
+        // The entry shown in the detail view is no longer loaded
+        assert_eq!(app.entry_detail, None);
+    }
+
+    #[test]
+    fn older_page_of_the_whole_limit_keeps_the_view_on_an_entry() {
+        let mut app = logs_app(MAX_LOG_ENTRIES, journal(MAX_LOG_ENTRIES..2 * MAX_LOG_ENTRIES));
+        assert_eq!(app.logs_scroll, MAX_LOG_ENTRIES - 1);
+
+        app.prepend_logs(journal(0..MAX_LOG_ENTRIES));
+        assert_eq!(app.logs.len(), MAX_LOG_ENTRIES);
+        assert_eq!(app.logs_scroll, MAX_LOG_ENTRIES - 1);
+        assert_eq!(app.current_panel_line(), Some(MAX_LOG_ENTRIES - 1));
+    }
+
+    #[test]
+    fn newer_page_evicts_the_oldest_entries() {
+        let mut app = logs_app(5, journal(0..MAX_LOG_ENTRIES));
+        app.logs_has_newer = true;
+        app.logs_scroll = 7;
+        app.open_entry_detail();
+        app.log_search_query = "entry 6.".to_string();
+        app.update_log_search();
+        assert_eq!(app.log_search_match_index, Some(0));
+
+        app.append_logs(journal(MAX_LOG_ENTRIES..MAX_LOG_ENTRIES + 5));
+        assert!(app.logs_has_older);
+        assert!(app.logs_has_newer);
+        assert_eq!(app.logs.len(), MAX_LOG_ENTRIES);
+        assert_eq!(app.log_lines.len(), MAX_LOG_ENTRIES);
+        assert_eq!(app.logs[0].message, "entry 5.");
+        assert_eq!(app.logs_scroll, 1);
+        assert_eq!(detail_message(&app), Some("entry 7."));
+        assert_eq!(app.log_search_matches, [1]);
+        assert_eq!(app.log_search_match_index, Some(0));
+
+        app.append_logs(journal(MAX_LOG_ENTRIES + 5..MAX_LOG_ENTRIES + 8));
+        assert!(!app.logs_has_newer);
+        assert_eq!(app.logs[0].message, "entry 8.");
+        assert_eq!(app.entry_detail, None);
+        assert!(app.log_search_matches.is_empty());
+        assert_eq!(app.log_search_match_index, None);
     }
 }
@@ -0,0 +1,470 @@
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use crate::dbus::{DbusClient, UnitEvent};
+use crate::deps::DependencyDirection;
+use crate::journal::{
+    fetch_boots, fetch_journal_units, fetch_log_page, fetch_logs, Boot, JournalEntry,
+    JournalSource, LogFollower, LogQuery, PageDirection,
+};
+use crate::properties::UnitProperties;
+use crate::service::{
+    daemon_reload, fetch_dependencies, fetch_properties, fetch_service, fetch_services,
+    fetch_timers, fetch_unit_file, run_action, Scope, ServiceAction, SystemdService,
+    SystemdTimer,
+};
+use crate::snapshot::Snapshot;
+
+#[cfg(test)]
+pub mod fake;
+
+/// Unit and journal access used by `App`.
+///
+/// Implementations are cloned onto worker threads for background loads.
+pub trait SystemdBackend: Clone + Send + 'static {
+    fn scope(&self) -> Scope;
+
+    /// Switches to the system or user service manager
+    fn set_scope(&mut self, scope: Scope);
+
+    /// Short name shown in the help screen
+    fn label(&self) -> &'static str;
+
+    /// Units of one type (all types for `None`), including installed but unloaded unit files
+    fn fetch_services(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String>;
+
+    /// Current state of one unit, `None` if systemd no longer knows it
+    fn fetch_service(&self, unit_name: &str) -> Result<Option<SystemdService>, String>;
+
+    fn fetch_properties(&self, unit_name: &str) -> Result<UnitProperties, String>;
+
+    /// The newest `query.lines` entries, oldest first
+    fn fetch_logs(&self, query: &LogQuery) -> Result<Vec<JournalEntry>, String>;
+
+    /// Up to `query.lines` entries directly before or after `cursor`, oldest first
+    fn fetch_log_page(
+        &self,
+        query: &LogQuery,
+        cursor: &str,
+        direction: PageDirection,
+    ) -> Result<Vec<JournalEntry>, String>;
+
+    /// Delivers entries of `query.unit` as they are written, starting after
+    /// `after_cursor` if given
+    fn follow_logs(
+        &self,
+        query: &LogQuery,
+        after_cursor: Option<&str>,
+    ) -> Result<LogFollower, String>;
+
+    /// Boots recorded in the journal, most recent first
+    fn fetch_boots(&self) -> Result<Vec<Boot>, String>;
+
+    /// Direct dependencies of a unit in one direction, with their current state
+    fn fetch_dependencies(
+        &self,
+        unit_name: &str,
+        direction: DependencyDirection,
+    ) -> Result<Vec<SystemdService>, String>;
+
+    fn fetch_timers(&self) -> Result<Vec<SystemdTimer>, String>;
+
+    /// The unit file and its drop-ins, with file headers rewritten into markers
+    fn fetch_unit_file(&self, unit_name: &str) -> Result<Vec<String>, String>;
+
+    /// Makes systemd pick up edited unit files
+    fn daemon_reload(&self) -> Result<(), String>;
+
+    /// Runs a state-changing action, returning its output for the status line
+    fn run_action(&self, unit_name: &str, action: ServiceAction) -> Result<String, String>;
+
+    /// Live unit changes, if the backend can push them
+    fn subscribe(&self) -> Option<Receiver<UnitEvent>> {
+        None
+    }
+
+    /// Journal that logs, boots and followed entries are read from
+    fn journal_source(&self) -> JournalSource {
+        JournalSource::Local
+    }
+
+    /// True when units do not come from the running system, which disables actions and
+    /// everything read straight from systemctl (unit files, dependencies, timers, edits)
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// True when the units belong to one service manager, so the scope cannot be switched
+    fn fixed_scope(&self) -> bool {
+        false
+    }
+}
+
+/// The real systemd: its D-Bus API, or `systemctl` when the bus is unavailable
+#[derive(Clone)]
+pub enum SystemBackend {
+    Dbus(Scope, Arc<DbusClient>),
+    Cli(Scope),
+}
+
+impl SystemBackend {
+    /// Prefers D-Bus and falls back to the CLI if the bus cannot be reached
+    pub fn connect(scope: Scope) -> Self {
+        match DbusClient::connect(scope) {
+            Ok(client) => SystemBackend::Dbus(scope, Arc::new(client)),
+            Err(_) => SystemBackend::Cli(scope),
+        }
+    }
+}
+
+impl SystemdBackend for SystemBackend {
+    fn scope(&self) -> Scope {
+        match self {
+            SystemBackend::Dbus(scope, _) | SystemBackend::Cli(scope) => *scope,
+        }
+    }
+
+    fn set_scope(&mut self, scope: Scope) {
+        *self = SystemBackend::connect(scope);
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SystemBackend::Dbus(..) => "D-Bus",
+            SystemBackend::Cli(_) => "systemctl",
+        }
+    }
+
+    fn fetch_services(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String> {
+        match self {
+            SystemBackend::Dbus(_, client) => client.list_units(unit_type),
+            SystemBackend::Cli(scope) => fetch_services(*scope, unit_type),
+        }
+    }
+
+    fn fetch_service(&self, unit_name: &str) -> Result<Option<SystemdService>, String> {
+        match self {
+            SystemBackend::Dbus(_, client) => client.get_unit(unit_name),
+            SystemBackend::Cli(scope) => fetch_service(*scope, unit_name),
+        }
+    }
+
+    fn fetch_properties(&self, unit_name: &str) -> Result<UnitProperties, String> {
+        match self {
+            SystemBackend::Dbus(_, client) => client.properties(unit_name),
+            SystemBackend::Cli(scope) => fetch_properties(*scope, unit_name),
+        }
+    }
+
+    fn fetch_logs(&self, query: &LogQuery) -> Result<Vec<JournalEntry>, String> {
+        fetch_logs(query)
+    }
+
+    fn fetch_log_page(
+        &self,
+        query: &LogQuery,
+        cursor: &str,
+        direction: PageDirection,
+    ) -> Result<Vec<JournalEntry>, String> {
+        fetch_log_page(query, cursor, direction)
+    }
+
+    fn follow_logs(
+        &self,
+        query: &LogQuery,
+        after_cursor: Option<&str>,
+    ) -> Result<LogFollower, String> {
+        LogFollower::spawn(query, after_cursor)
+    }
+
+    fn fetch_boots(&self) -> Result<Vec<Boot>, String> {
+        fetch_boots(&JournalSource::Local)
+    }
+
+    fn fetch_dependencies(
+        &self,
+        unit_name: &str,
+        direction: DependencyDirection,
+    ) -> Result<Vec<SystemdService>, String> {
+        fetch_dependencies(self.scope(), unit_name, direction)
+    }
+
+    fn fetch_timers(&self) -> Result<Vec<SystemdTimer>, String> {
+        fetch_timers(self.scope())
+    }
+
+    fn fetch_unit_file(&self, unit_name: &str) -> Result<Vec<String>, String> {
+        fetch_unit_file(self.scope(), unit_name)
+    }
+
+    fn daemon_reload(&self) -> Result<(), String> {
+        daemon_reload(self.scope())
+    }
+
+    fn run_action(&self, unit_name: &str, action: ServiceAction) -> Result<String, String> {
+        // Actions go through systemctl either way, for its polkit prompts and messages
+        run_action(self.scope(), unit_name, action)
+    }
+
+    fn subscribe(&self) -> Option<Receiver<UnitEvent>> {
+        match self {
+            SystemBackend::Dbus(_, client) => client.subscribe().ok(),
+            SystemBackend::Cli(_) => None,
+        }
+    }
+}
+
+/// A journal read offline, e.g. copied from another machine.
+///
+/// Units are the ones that logged something; their live state is unknown
+/// and actions are refused.
+#[derive(Clone)]
+pub struct JournalBackend {
+    scope: Scope,
+    source: JournalSource,
+}
+
+impl JournalBackend {
+    pub fn new(scope: Scope, source: JournalSource) -> Self {
+        Self { scope, source }
+    }
+}
+
+impl SystemdBackend for JournalBackend {
+    fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    fn set_scope(&mut self, scope: Scope) {
+        self.scope = scope;
+    }
+
+    fn label(&self) -> &'static str {
+        "journal"
+    }
+
+    fn fetch_services(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String> {
+        Ok(fetch_journal_units(&self.source, self.scope)?
+            .into_iter()
+            .map(SystemdService::from_journal)
+            .filter(|s| unit_type.is_none_or(|t| s.unit_type() == t))
+            .collect())
+    }
+
+    fn fetch_service(&self, unit_name: &str) -> Result<Option<SystemdService>, String> {
+        Ok(self
+            .fetch_services(None)?
+            .into_iter()
+            .find(|s| s.unit == unit_name))
+    }
+
+    fn fetch_properties(&self, unit_name: &str) -> Result<UnitProperties, String> {
+        Err(format!(
+            "Properties of {} are not recorded in the journal.",
+            unit_name
+        ))
+    }
+
+    fn fetch_logs(&self, query: &LogQuery) -> Result<Vec<JournalEntry>, String> {
+        fetch_logs(query)
+    }
+
+    fn fetch_log_page(
+        &self,
+        query: &LogQuery,
+        cursor: &str,
+        direction: PageDirection,
+    ) -> Result<Vec<JournalEntry>, String> {
+        fetch_log_page(query, cursor, direction)
+    }
+
+    fn follow_logs(
+        &self,
+        query: &LogQuery,
+        after_cursor: Option<&str>,
+    ) -> Result<LogFollower, String> {
+        LogFollower::spawn(query, after_cursor)
+    }
+
+    fn fetch_boots(&self) -> Result<Vec<Boot>, String> {
+        fetch_boots(&self.source)
+    }
+
+    fn fetch_dependencies(
+        &self,
+        unit_name: &str,
+        _direction: DependencyDirection,
+    ) -> Result<Vec<SystemdService>, String> {
+        Err(format!(
+            "Dependencies of {} are not recorded in the journal.",
+            unit_name
+        ))
+    }
+
+    fn fetch_timers(&self) -> Result<Vec<SystemdTimer>, String> {
+        Err("Timers are not recorded in the journal.".to_string())
+    }
+
+    fn fetch_unit_file(&self, unit_name: &str) -> Result<Vec<String>, String> {
+        Err(format!(
+            "The unit file of {} is not recorded in the journal.",
+            unit_name
+        ))
+    }
+
+    fn daemon_reload(&self) -> Result<(), String> {
+        Err("Cannot reload: reading an offline journal.".to_string())
+    }
+
+    fn run_action(&self, unit_name: &str, action: ServiceAction) -> Result<String, String> {
+        Err(format!(
+            "Cannot {} {}: reading an offline journal.",
+            action.verb(),
+            unit_name
+        ))
+    }
+
+    fn journal_source(&self) -> JournalSource {
+        self.source.clone()
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+}
+
+/// Units from a saved snapshot; logs come from an offline journal if one is given
+#[derive(Clone)]
+pub struct SnapshotBackend {
+    scope: Scope,
+    snapshot: Arc<Snapshot>,
+    source: JournalSource,
+}
+
+impl SnapshotBackend {
+    /// `scope` is only used for snapshots that do not record their own
+    pub fn new(scope: Scope, snapshot: Snapshot, source: JournalSource) -> Self {
+        Self {
+            scope: snapshot.scope.unwrap_or(scope),
+            snapshot: Arc::new(snapshot),
+            source,
+        }
+    }
+
+    /// The journal given alongside the snapshot; the local one belongs to a different machine
+    fn journal(&self) -> Result<&JournalSource, String> {
+        if self.source == JournalSource::Local {
+            return Err(
+                "Logs are not part of a snapshot; add --journal-dir or --journal-file.".to_string(),
+            );
+        }
+        Ok(&self.source)
+    }
+}
+
+impl SystemdBackend for SnapshotBackend {
+    fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// The units were captured in one scope; see `fixed_scope`
+    fn set_scope(&mut self, _scope: Scope) {}
+
+    fn label(&self) -> &'static str {
+        "snapshot"
+    }
+
+    fn fetch_services(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String> {
+        Ok(self
+            .snapshot
+            .units
+            .iter()
+            .filter(|s| unit_type.is_none_or(|t| s.unit_type() == t))
+            .cloned()
+            .collect())
+    }
+
+    fn fetch_service(&self, unit_name: &str) -> Result<Option<SystemdService>, String> {
+        Ok(self.snapshot.units.iter().find(|s| s.unit == unit_name).cloned())
+    }
+
+    fn fetch_properties(&self, unit_name: &str) -> Result<UnitProperties, String> {
+        self.snapshot
+            .unit_properties(unit_name)
+            .ok_or_else(|| format!("No properties were captured for {}.", unit_name))
+    }
+
+    fn fetch_logs(&self, query: &LogQuery) -> Result<Vec<JournalEntry>, String> {
+        self.journal()?;
+        fetch_logs(query)
+    }
+
+    fn fetch_log_page(
+        &self,
+        query: &LogQuery,
+        cursor: &str,
+        direction: PageDirection,
+    ) -> Result<Vec<JournalEntry>, String> {
+        self.journal()?;
+        fetch_log_page(query, cursor, direction)
+    }
+
+    fn follow_logs(
+        &self,
+        query: &LogQuery,
+        after_cursor: Option<&str>,
+    ) -> Result<LogFollower, String> {
+        self.journal()?;
+        LogFollower::spawn(query, after_cursor)
+    }
+
+    fn fetch_boots(&self) -> Result<Vec<Boot>, String> {
+        fetch_boots(self.journal()?)
+    }
+
+    fn fetch_dependencies(
+        &self,
+        unit_name: &str,
+        _direction: DependencyDirection,
+    ) -> Result<Vec<SystemdService>, String> {
+        Err(format!(
+            "Dependencies of {} were not captured in the snapshot.",
+            unit_name
+        ))
+    }
+
+    fn fetch_timers(&self) -> Result<Vec<SystemdTimer>, String> {
+        Err("Timers were not captured in the snapshot.".to_string())
+    }
+
+    fn fetch_unit_file(&self, unit_name: &str) -> Result<Vec<String>, String> {
+        Err(format!(
+            "The unit file of {} was not captured in the snapshot.",
+            unit_name
+        ))
+    }
+
+    fn daemon_reload(&self) -> Result<(), String> {
+        Err("Cannot reload: viewing a snapshot.".to_string())
+    }
+
+    fn run_action(&self, unit_name: &str, action: ServiceAction) -> Result<String, String> {
+        Err(format!(
+            "Cannot {} {}: viewing a snapshot.",
+            action.verb(),
+            unit_name
+        ))
+    }
+
+    fn journal_source(&self) -> JournalSource {
+        self.source.clone()
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    fn fixed_scope(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::SystemdBackend;
+use crate::app::App;
+use crate::deps::DependencyDirection;
+use crate::journal::{Boot, JournalEntry, LogFollower, LogQuery, PageDirection};
+use crate::properties::UnitProperties;
+use crate::service::{Scope, ServiceAction, SystemdService, SystemdTimer, UNIT_FILE_MAIN_MARKER};
+
+/// An in-memory service manager: actions change the stored units the way systemd would
+#[derive(Clone, Default)]
+pub struct FakeBackend {
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    scope: Scope,
+    services: Vec<SystemdService>,
+    logs: HashMap<String, Vec<JournalEntry>>,
+    followers: Vec<(String, Sender<JournalEntry>)>,
+    actions: Vec<(String, ServiceAction)>,
+}
+
+/// A loaded, enabled unit in the given state
+pub fn unit(name: &str, active: &str, sub: &str, description: &str) -> SystemdService {
+    SystemdService {
+        unit: name.to_string(),
+        load: "loaded".to_string(),
+        active: active.to_string(),
+        sub: sub.to_string(),
+        description: description.to_string(),
+        unit_file_state: Some("enabled".to_string()),
+    }
+}
+
+/// A few units of different types and states, with logs for cron.service
+pub fn sample() -> FakeBackend {
+    FakeBackend::new(vec![
+        unit("cron.service", "active", "running", "Regular background program processing"),
+        unit("nginx.service", "failed", "failed", "A high performance web server"),
+        unit("ssh.service", "active", "running", "OpenBSD Secure Shell server"),
+        unit("logrotate.timer", "active", "waiting", "Daily rotation of log files"),
+        unit("tmp.mount", "active", "mounted", "Temporary Directory /tmp"),
+    ])
+    .with_logs("cron.service", &["started", "job ran", "job failed"])
+}
+
+/// An app on `backend` with its initial unit list loaded
+pub fn loaded_app<B: SystemdBackend>(backend: B) -> App<B> {
+    let mut app = App::new(backend);
+    settle(&mut app);
+    app
+}
+
+/// Ticks the app until background loads have finished
+pub fn settle<B: SystemdBackend>(app: &mut App<B>) {
+    for _ in 0..1000 {
+        // Idle before the tick means every result has been sent and the tick applies it
+        let idle = !app.busy();
+        app.tick();
+        if idle && !app.busy() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    panic!("background load did not finish");
+}
+
+impl FakeBackend {
+    pub fn new(services: Vec<SystemdService>) -> Self {
+        let backend = Self::default();
+        backend.state.lock().unwrap().services = services;
+        backend
+    }
+
+    /// Adds journal entries for `unit`, one per message
+    pub fn with_logs(self, unit: &str, messages: &[&str]) -> Self {
+        for message in messages {
+            self.log(unit, message);
+        }
+        self
+    }
+
+    /// Writes one journal entry for `unit`, passing it to any follower of the unit
+    pub fn log(&self, unit: &str, message: &str) {
+        let mut state = self.state.lock().unwrap();
+        let entries = state.logs.entry(unit.to_string()).or_default();
+        let i = entries.len();
+        let entry = JournalEntry {
+            realtime_usec: Some(1_700_000_000_000_000 + i as u64 * 1_000_000),
+            priority: Some(6),
+            identifier: Some(unit.trim_end_matches(".service").to_string()),
+            unit: Some(unit.to_string()),
+            message: message.to_string(),
+            cursor: Some(format!("s=fake;i={}", i)),
+            ..JournalEntry::default()
+        };
+        entries.push(entry.clone());
+        state
+            .followers
+            .retain(|(followed, tx)| followed != unit || tx.send(entry.clone()).is_ok());
+    }
+
+    /// Overwrites a unit's state, as if it changed outside the app
+    pub fn set_state(&self, unit: &str, active: &str, sub: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(service) = state.services.iter_mut().find(|s| s.unit == unit) {
+            service.active = active.to_string();
+            service.sub = sub.to_string();
+        }
+    }
+
+    /// Actions run so far, oldest first
+    pub fn actions(&self) -> Vec<(String, ServiceAction)> {
+        self.state.lock().unwrap().actions.clone()
+    }
+
+    /// Entries of `query.unit` that pass its priority filter, oldest first
+    fn entries(&self, query: &LogQuery) -> Vec<JournalEntry> {
+        let state = self.state.lock().unwrap();
+        state
+            .logs
+            .get(&query.unit)
+            .into_iter()
+            .flatten()
+            .filter(|e| query.priority.is_none_or(|p| e.priority.is_none_or(|ep| ep <= p)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl SystemdBackend for FakeBackend {
+    fn scope(&self) -> Scope {
+        self.state.lock().unwrap().scope
+    }
+
+    fn set_scope(&mut self, scope: Scope) {
+        self.state.lock().unwrap().scope = scope;
+    }
+
+    fn label(&self) -> &'static str {
+        "fake"
+    }
+
+    fn fetch_services(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .services
+            .iter()
+            .filter(|s| unit_type.is_none_or(|t| s.unit_type() == t))
+            .cloned()
+            .collect())
+    }
+
+    fn fetch_service(&self, unit_name: &str) -> Result<Option<SystemdService>, String> {
+        let state = self.state.lock().unwrap();
+        Ok(state.services.iter().find(|s| s.unit == unit_name).cloned())
+    }
+
+    fn fetch_properties(&self, unit_name: &str) -> Result<UnitProperties, String> {
+        let service = self
+            .fetch_service(unit_name)?
+            .ok_or_else(|| format!("Unit {} not found.", unit_name))?;
+        Ok(UnitProperties::parse(&format!(
+            "Id={}\nDescription={}\nActiveState={}\nSubState={}",
+            service.unit, service.description, service.active, service.sub
+        )))
+    }
+
+    fn fetch_logs(&self, query: &LogQuery) -> Result<Vec<JournalEntry>, String> {
+        let entries = self.entries(query);
+        let skip = entries.len().saturating_sub(query.lines);
+        Ok(entries.into_iter().skip(skip).collect())
+    }
+
+    fn fetch_log_page(
+        &self,
+        query: &LogQuery,
+        cursor: &str,
+        direction: PageDirection,
+    ) -> Result<Vec<JournalEntry>, String> {
+        let entries = self.entries(query);
+        let i = entries
+            .iter()
+            .position(|e| e.cursor.as_deref() == Some(cursor))
+            .ok_or_else(|| format!("Unknown cursor {}", cursor))?;
+        let page = match direction {
+            PageDirection::Older => &entries[i.saturating_sub(query.lines)..i],
+            PageDirection::Newer => &entries[i + 1..(i + 1 + query.lines).min(entries.len())],
+        };
+        Ok(page.to_vec())
+    }
+
+    fn follow_logs(
+        &self,
+        query: &LogQuery,
+        after_cursor: Option<&str>,
+    ) -> Result<LogFollower, String> {
+        let (tx, rx) = mpsc::channel();
+        if let Some(cursor) = after_cursor {
+            let entries = self.entries(query);
+            let i = entries
+                .iter()
+                .position(|e| e.cursor.as_deref() == Some(cursor))
+                .ok_or_else(|| format!("Unknown cursor {}", cursor))?;
+            for entry in &entries[i + 1..] {
+                let _ = tx.send(entry.clone());
+            }
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .followers
+            .push((query.unit.clone(), tx));
+        Ok(LogFollower::from_channel(rx))
+    }
+
+    fn fetch_boots(&self) -> Result<Vec<Boot>, String> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_dependencies(
+        &self,
+        _unit_name: &str,
+        _direction: DependencyDirection,
+    ) -> Result<Vec<SystemdService>, String> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_timers(&self) -> Result<Vec<SystemdTimer>, String> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_unit_file(&self, unit_name: &str) -> Result<Vec<String>, String> {
+        let service = self
+            .fetch_service(unit_name)?
+            .ok_or_else(|| format!("No files found for {}.", unit_name))?;
+        Ok(vec![
+            format!("{}/etc/systemd/system/{}", UNIT_FILE_MAIN_MARKER, service.unit),
+            "[Unit]".to_string(),
+            format!("Description={}", service.description),
+        ])
+    }
+
+    fn daemon_reload(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn run_action(&self, unit_name: &str, action: ServiceAction) -> Result<String, String> {
+        let mut state = self.state.lock().unwrap();
+        state.actions.push((unit_name.to_string(), action));
+        let Some(service) = state.services.iter_mut().find(|s| s.unit == unit_name) else {
+            return Err(format!("Unit {} not found.", unit_name));
+        };
+        let set = |service: &mut SystemdService, active: &str, sub: &str| {
+            service.active = active.to_string();
+            service.sub = sub.to_string();
+        };
+        match action {
+            ServiceAction::Start | ServiceAction::Restart | ServiceAction::Reload => {
+                set(service, "active", "running");
+            }
+            ServiceAction::TryRestart if service.active == "active" => {
+                set(service, "active", "running");
+            }
+            ServiceAction::TryRestart => {}
+            ServiceAction::Stop => set(service, "inactive", "dead"),
+            ServiceAction::Enable => service.unit_file_state = Some("enabled".to_string()),
+            ServiceAction::Disable | ServiceAction::Unmask => {
+                service.unit_file_state = Some("disabled".to_string());
+            }
+            ServiceAction::Mask => service.unit_file_state = Some("masked".to_string()),
+            ServiceAction::EnableNow => {
+                service.unit_file_state = Some("enabled".to_string());
+                set(service, "active", "running");
+            }
+        }
+        Ok(String::new())
+    }
+}
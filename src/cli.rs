@@ -0,0 +1,345 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::app::{App, MAX_LOG_ENTRIES, UNIT_TYPE_OPTIONS};
+use crate::backend::SystemdBackend;
+use crate::config::Config;
+use crate::journal::{JournalSource, PRIORITY_NAMES};
+use crate::service::Scope;
+
+/// Browse systemd units and their journal logs
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    #[command(flatten)]
+    pub startup: StartupArgs,
+
+    /// Show the per-user service manager instead of the system one
+    #[arg(long, global = true)]
+    pub user: bool,
+
+    /// Read logs from a journal directory instead of the running system,
+    /// e.g. /var/log/journal copied from another machine
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "journal_file")]
+    pub journal_dir: Option<PathBuf>,
+
+    /// Read logs from a journal file instead of the running system; repeat for several files
+    #[arg(long, global = true, value_name = "FILE")]
+    pub journal_file: Vec<PathBuf>,
+
+    /// Browse units read-only from a snapshot: a file written by --save-snapshot,
+    /// or `systemctl list-units --all --output=json` output
+    #[arg(long, global = true, value_name = "FILE")]
+    pub from_snapshot: Option<PathBuf>,
+
+    /// Read settings from this file instead of ~/.config/systemdview/config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Write the current units and their properties to a snapshot file and exit
+    #[arg(long, value_name = "FILE", conflicts_with = "from_snapshot")]
+    pub save_snapshot: Option<PathBuf>,
+}
+
+/// How the UI starts out
+#[derive(Debug, Args)]
+pub struct StartupArgs {
+    /// Start with this search text in the unit list
+    #[arg(short, long, value_name = "TEXT")]
+    pub search: Option<String>,
+
+    /// Start with the unit list filtered to units in this state
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Start with the logs panel open on this unit
+    #[arg(
+        short,
+        long,
+        value_name = "UNIT",
+        value_parser = parse_unit_name,
+        conflicts_with_all = ["search", "status"]
+    )]
+    pub unit: Option<String>,
+
+    /// Journal entries to load at once in the logs panel, and per page when scrolling
+    /// [default: logs.lines of the config, or 1000]
+    #[arg(short = 'n', long, value_parser = parse_log_lines)]
+    pub lines: Option<usize>,
+
+    /// Refresh the unit list every SECS seconds; 0 turns auto-refresh off
+    #[arg(long, value_name = "SECS")]
+    pub refresh: Option<u64>,
+
+    /// Leave the mouse to the terminal, e.g. for selecting text
+    #[arg(long)]
+    pub no_mouse: bool,
+}
+
+impl StartupArgs {
+    /// Sets the app up the way the options ask for, before the first frame
+    pub fn apply<B: SystemdBackend>(&self, app: &mut App<B>) {
+        if let Some(lines) = self.lines {
+            app.log_page_size = lines;
+        }
+        if self.no_mouse {
+            app.mouse = false;
+        }
+        if let Some(secs) = self.refresh {
+            app.refresh_interval = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Some(ref unit) = self.unit {
+            app.open_logs(unit);
+        }
+        if let Some(ref search) = self.search {
+            app.search_query = search.clone();
+        }
+        if let Some(ref status) = self.status {
+            app.status_filter = Some(status.clone());
+        }
+        app.update_filter();
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Print units, filtered the same way as the unit list
+    List(ListArgs),
+    /// Print the journal of a unit
+    Logs(LogsArgs),
+    /// Print the properties of a unit
+    Show(ShowArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only units in this state, one of the status filters of the unit list
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// Only units whose name or description contains this text, ignoring case
+    #[arg(long, value_name = "TEXT")]
+    pub filter: Option<String>,
+
+    /// Unit type, or "all" for every type
+    #[arg(long = "type", value_name = "TYPE", default_value = "service", value_parser = parse_unit_type)]
+    pub unit_type: String,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Args)]
+pub struct LogsArgs {
+    /// Unit name; the ".service" suffix may be left out
+    pub unit: String,
+
+    /// Any timestamp or relative expression journalctl accepts, e.g. "-1h" or "yesterday"
+    #[arg(long, value_name = "WHEN")]
+    pub since: Option<String>,
+
+    /// End of the time range, in the same forms as --since
+    #[arg(long, value_name = "WHEN")]
+    pub until: Option<String>,
+
+    /// Only entries of this boot: 0 for the current one, -1 for the one before, ...
+    #[arg(short, long, value_name = "OFFSET", allow_negative_numbers = true)]
+    pub boot: Option<i32>,
+
+    /// Only entries at this priority or more severe, by name (e.g. "err") or number
+    #[arg(short, long, value_parser = parse_priority)]
+    pub priority: Option<u8>,
+
+    /// Number of most recent entries to print [default: logs.lines of the config, or 1000]
+    #[arg(short = 'n', long, value_parser = parse_log_lines)]
+    pub lines: Option<usize>,
+
+    /// Keep printing new entries as they arrive
+    #[arg(short, long)]
+    pub follow: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    /// Unit name; the ".service" suffix may be left out
+    #[arg(value_parser = parse_unit_name)]
+    pub unit: String,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+impl Cli {
+    /// Checks the options that depend on the config, e.g. configured status filters
+    pub fn validate(&self, config: &Config) -> Result<(), String> {
+        let status = match self.command {
+            Some(CliCommand::List(ref args)) => args.status.as_ref(),
+            _ => self.startup.status.as_ref(),
+        };
+        match status {
+            Some(status) if !config.status.filters.contains(status) => Err(format!(
+                "invalid value '{}' for '--status <STATUS>'\n  [possible values: {}]",
+                status,
+                config.status.filters.join(", ")
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn scope(&self) -> Scope {
+        if self.user { Scope::User } else { Scope::System }
+    }
+
+    pub fn journal_source(&self) -> JournalSource {
+        if let Some(ref dir) = self.journal_dir {
+            JournalSource::Directory(dir.clone())
+        } else if !self.journal_file.is_empty() {
+            JournalSource::Files(self.journal_file.clone())
+        } else {
+            JournalSource::Local
+        }
+    }
+}
+
+fn parse_unit_type(value: &str) -> Result<String, String> {
+    let value = value.to_lowercase();
+    if UNIT_TYPE_OPTIONS.iter().any(|t| t.to_lowercase() == value) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "expected \"all\" or one of {}",
+            UNIT_TYPE_OPTIONS[1..].join(", ")
+        ))
+    }
+}
+
+/// Unit names default to services, as with `systemctl status nginx`
+fn parse_unit_name(value: &str) -> Result<String, String> {
+    if value.contains('.') {
+        Ok(value.to_string())
+    } else {
+        Ok(format!("{}.service", value))
+    }
+}
+
+fn parse_log_lines(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(lines) if (1..=MAX_LOG_ENTRIES).contains(&lines) => Ok(lines),
+        _ => Err(format!("expected a number from 1 to {}", MAX_LOG_ENTRIES)),
+    }
+}
+
+fn parse_priority(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(level) if (level as usize) < PRIORITY_NAMES.len() => Ok(level),
+        _ => PRIORITY_NAMES
+            .iter()
+            .position(|name| *name == value)
+            .map(|level| level as u8)
+            .ok_or_else(|| format!("expected 0-7 or one of {}", PRIORITY_NAMES.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::PanelMode;
+    use crate::backend::fake::{sample, settle};
+    use clap::CommandFactory;
+
+    #[test]
+    fn arguments_are_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_list_filters() {
+        let cli = Cli::parse_from([
+            "systemdview", "list", "--status", "failed", "--filter", "nginx", "--type", "All",
+            "--format", "csv", "--user",
+        ]);
+        let Some(CliCommand::List(ref args)) = cli.command else {
+            panic!("expected the list subcommand");
+        };
+        assert_eq!(args.status.as_deref(), Some("failed"));
+        assert_eq!(args.filter.as_deref(), Some("nginx"));
+        assert_eq!(args.unit_type, "all");
+        assert_eq!(args.format, OutputFormat::Csv);
+        assert!(cli.user);
+        assert!(cli.validate(&Config::default()).is_ok());
+        let bogus = Cli::parse_from(["systemdview", "list", "--status", "bogus"]);
+        assert!(bogus.validate(&Config::default()).is_err());
+    }
+
+    #[test]
+    fn parses_log_options() {
+        let cli = Cli::parse_from(["systemdview", "logs", "ssh", "-b", "-1", "-p", "warning"]);
+        let Some(CliCommand::Logs(args)) = cli.command else {
+            panic!("expected the logs subcommand");
+        };
+        assert_eq!(args.unit, "ssh");
+        assert_eq!(args.boot, Some(-1));
+        assert_eq!(args.priority, Some(4));
+        assert_eq!(args.lines, None);
+    }
+
+    #[test]
+    fn parses_show_options() {
+        let cli = Cli::parse_from(["systemdview", "show", "nginx", "--format", "json"]);
+        let Some(CliCommand::Show(args)) = cli.command else {
+            panic!("expected the show subcommand");
+        };
+        assert_eq!(args.unit, "nginx.service");
+        assert_eq!(args.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn startup_options_configure_the_app() {
+        let cli = Cli::parse_from([
+            "systemdview", "--search", "SHELL", "--status", "running", "-n", "50", "--refresh",
+            "5", "--no-mouse",
+        ]);
+        let mut app = App::new(sample());
+        cli.startup.apply(&mut app);
+        settle(&mut app);
+
+        let shown: Vec<&str> = app
+            .filtered_indices
+            .iter()
+            .map(|&i| app.services[i].unit.as_str())
+            .collect();
+        assert_eq!(shown, ["ssh.service"]);
+        assert_eq!(app.log_page_size, 50);
+        assert_eq!(app.refresh_interval, Some(Duration::from_secs(5)));
+        assert!(!app.mouse);
+    }
+
+    #[test]
+    fn startup_unit_opens_its_logs() {
+        let cli = Cli::parse_from(["systemdview", "--unit", "cron"]);
+        let mut app = App::new(sample());
+        cli.startup.apply(&mut app);
+        settle(&mut app);
+
+        assert_eq!(app.selected_service().unwrap().unit, "cron.service");
+        assert!(app.show_logs);
+        assert_eq!(app.panel_mode, PanelMode::Logs);
+        // The selection arrived with the unit list; the next tick loads its logs
+        app.tick();
+        settle(&mut app);
+        assert_eq!(app.logs.len(), 3);
+        assert!(Cli::try_parse_from(["systemdview", "--unit", "cron", "--search", "x"]).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use crate::backend::SystemdBackend;
+use crate::cli::{ListArgs, LogsArgs, OutputFormat, ShowArgs};
+use crate::config::Config;
+use crate::journal::{LogColumn, LogQuery};
+use crate::properties::UnitProperties;
+use crate::service::{ServiceFilter, SystemdService};
+use crate::snapshot::Snapshot;
+
+/// How often `logs --follow` checks for new entries
+const FOLLOW_POLL: Duration = Duration::from_millis(200);
+
+/// Reports an error the way command-line tools do and exits
+fn fail(message: &str) -> ! {
+    eprintln!("systemdview: {}", message);
+    process::exit(1);
+}
+
+/// `systemdview list`: units of `backend` matching the same filter as the unit list
+pub fn list<B: SystemdBackend>(backend: &B, args: &ListArgs) -> io::Result<()> {
+    let unit_type = (args.unit_type != "all").then_some(args.unit_type.as_str());
+    let services = backend
+        .fetch_services(unit_type)
+        .unwrap_or_else(|e| fail(&e));
+    let filter = ServiceFilter::new(args.filter.as_deref().unwrap_or(""), args.status.as_deref());
+    let services: Vec<&SystemdService> = services.iter().filter(|s| filter.matches(s)).collect();
+    let rows: Vec<[&str; 6]> = services.iter().map(|s| columns(s)).collect();
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        OutputFormat::Table => write_table(&mut out, HEADERS, &rows),
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &services)?;
+            writeln!(out)
+        }
+        OutputFormat::Csv => write_csv(&mut out, HEADERS, &rows),
+    }
+}
+
+/// `systemdview show`: every property of a unit, with values as `systemctl show` prints them
+pub fn show<B: SystemdBackend>(backend: &B, args: &ShowArgs) -> io::Result<()> {
+    let properties = backend
+        .fetch_properties(&args.unit)
+        .unwrap_or_else(|e| fail(&e));
+    write_properties(&mut io::stdout().lock(), &properties, args.format)
+}
+
+const PROPERTY_HEADERS: [&str; 2] = ["PROPERTY", "VALUE"];
+
+fn write_properties(
+    out: &mut impl Write,
+    properties: &UnitProperties,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let values: Vec<(&str, String)> = properties
+        .properties
+        .iter()
+        .map(|p| (p.key.as_str(), p.value.raw()))
+        .collect();
+    let rows: Vec<[&str; 2]> = values.iter().map(|(key, value)| [*key, value.as_str()]).collect();
+    match format {
+        OutputFormat::Table => write_table(out, PROPERTY_HEADERS, &rows),
+        OutputFormat::Json => {
+            let object: serde_json::Map<String, serde_json::Value> = values
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.into()))
+                .collect();
+            serde_json::to_writer_pretty(&mut *out, &object)?;
+            writeln!(out)
+        }
+        OutputFormat::Csv => write_csv(out, PROPERTY_HEADERS, &rows),
+    }
+}
+
+fn columns(service: &SystemdService) -> [&str; 6] {
+    [
+        &service.unit,
+        &service.load,
+        &service.active,
+        &service.sub,
+        service.unit_file_state.as_deref().unwrap_or(""),
+        &service.description,
+    ]
+}
+
+const HEADERS: [&str; 6] = ["UNIT", "LOAD", "ACTIVE", "SUB", "ENABLED", "DESCRIPTION"];
+
+/// Aligned columns like `systemctl list-units`
+fn write_table<const N: usize>(
+    out: &mut impl Write,
+    headers: [&str; N],
+    rows: &[[&str; N]],
+) -> io::Result<()> {
+    let mut widths = headers.map(str::len);
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+
+    let mut write_row = |row: [&str; N]| {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(value, width)| format!("{:width$}", value, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())
+    };
+    write_row(headers)?;
+    for row in rows {
+        write_row(*row)?;
+    }
+    Ok(())
+}
+
+fn write_csv<const N: usize>(
+    out: &mut impl Write,
+    headers: [&str; N],
+    rows: &[[&str; N]],
+) -> io::Result<()> {
+    let row = |values: [&str; N]| {
+        values
+            .iter()
+            .map(|value| csv_field(value))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    writeln!(out, "{}", row(headers).to_lowercase())?;
+    for values in rows {
+        writeln!(out, "{}", row(*values))?;
+    }
+    Ok(())
+}
+
+/// Quotes a field containing separators, quotes or line breaks (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `systemdview logs`: a unit's entries as journalctl's short output would show them
+pub fn logs<B: SystemdBackend>(backend: &B, args: &LogsArgs, config: &Config) -> io::Result<()> {
+    let query = LogQuery {
+        source: backend.journal_source(),
+        scope: backend.scope(),
+        unit: args.unit.clone(),
+        lines: args.lines.unwrap_or(config.logs.lines),
+        priority: args.priority,
+        since: args.since.clone(),
+        until: args.until.clone(),
+        boot: args.boot,
+    };
+    let entries = backend.fetch_logs(&query).unwrap_or_else(|e| fail(&e));
+
+    let mut out = io::stdout().lock();
+    for entry in &entries {
+        writeln!(out, "{}", entry.format(&LogColumn::DEFAULT))?;
+    }
+    if !args.follow {
+        return Ok(());
+    }
+
+    out.flush()?;
+    // Start right after the last printed entry so nothing written in between is lost
+    let after = entries.last().and_then(|entry| entry.cursor.as_deref());
+    let follower = backend.follow_logs(&query, after).unwrap_or_else(|e| fail(&e));
+    loop {
+        for entry in follower.drain() {
+            writeln!(out, "{}", entry.format(&LogColumn::DEFAULT))?;
+        }
+        out.flush()?;
+        thread::sleep(FOLLOW_POLL);
+    }
+}
+
+/// `--save-snapshot`: captures `backend` into a snapshot file
+pub fn save_snapshot<B: SystemdBackend>(backend: &B, path: &Path) -> io::Result<()> {
+    let snapshot = Snapshot::capture(backend).unwrap_or_else(|e| fail(&e));
+    snapshot.save(path).unwrap_or_else(|e| fail(&e));
+    println!("Saved {} units to {}", snapshot.units.len(), path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{sample, unit};
+
+    fn output(write: impl Fn(&mut Vec<u8>, [&str; 6], &[[&str; 6]]) -> io::Result<()>) -> String {
+        let services = [
+            unit("ssh.service", "active", "running", "OpenBSD Secure Shell server"),
+            unit("web.service", "failed", "failed", "Web server, \"fast\""),
+        ];
+        let rows: Vec<[&str; 6]> = services.iter().map(columns).collect();
+        let mut out = Vec::new();
+        write(&mut out, HEADERS, &rows).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn table_aligns_columns() {
+        assert_eq!(
+            output(write_table),
+            "UNIT         LOAD    ACTIVE  SUB      ENABLED  DESCRIPTION\n\
+             ssh.service  loaded  active  running  enabled  OpenBSD Secure Shell server\n\
+             web.service  loaded  failed  failed   enabled  Web server, \"fast\"\n"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_fields() {
+        assert_eq!(
+            output(write_csv),
+            "unit,load,active,sub,enabled,description\n\
+             ssh.service,loaded,active,running,enabled,OpenBSD Secure Shell server\n\
+             web.service,loaded,failed,failed,enabled,\"Web server, \"\"fast\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn properties_in_every_format() {
+        let properties = sample().fetch_properties("nginx.service").unwrap();
+        let output = |format| {
+            let mut out = Vec::new();
+            write_properties(&mut out, &properties, format).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            output(OutputFormat::Table),
+            "PROPERTY     VALUE\n\
+             Id           nginx.service\n\
+             Description  A high performance web server\n\
+             ActiveState  failed\n\
+             SubState     failed\n"
+        );
+        assert_eq!(
+            output(OutputFormat::Csv),
+            "property,value\n\
+             Id,nginx.service\n\
+             Description,A high performance web server\n\
+             ActiveState,failed\n\
+             SubState,failed\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&output(OutputFormat::Json)).unwrap();
+        assert_eq!(json["ActiveState"], "failed");
+        assert_eq!(json["Id"], "nginx.service");
+    }
+}
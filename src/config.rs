@@ -0,0 +1,338 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::app::{
+    App, LIST_WIDTH, LOG_PAGE_SIZE, MAX_LOG_ENTRIES, STATUS_OPTIONS, UNIT_TYPE_OPTIONS,
+};
+use crate::backend::SystemdBackend;
+use crate::keymap::Keymap;
+
+/// Settings read from `config.toml`; every key is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Keys of the main views by action, e.g. `quit = "Q"`
+    pub keys: BTreeMap<String, String>,
+    pub layout: LayoutConfig,
+    pub logs: LogsConfig,
+    pub startup: StartupConfig,
+    pub status: StatusConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    /// Width of the unit list in percent while the side panel is open
+    pub list_width: u16,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            list_width: LIST_WIDTH,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogsConfig {
+    /// Journal entries loaded at once, and per page when scrolling
+    pub lines: usize,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            lines: LOG_PAGE_SIZE,
+        }
+    }
+}
+
+/// How the UI starts out; command-line options take precedence
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StartupConfig {
+    pub search: Option<String>,
+    pub status: Option<String>,
+    /// A unit type such as "timer", or "all"
+    pub unit_type: String,
+    /// Auto-refresh interval in seconds, 0 for off
+    pub refresh: u64,
+    pub show_logs: bool,
+    pub mouse: bool,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            search: None,
+            status: None,
+            unit_type: "service".to_string(),
+            refresh: 0,
+            show_logs: false,
+            mouse: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatusConfig {
+    /// Sub-states offered by the status filter after "All"
+    pub filters: Vec<String>,
+    /// Colours by sub-state, e.g. `failed = "lightred"`, overriding the built-in ones
+    pub colors: BTreeMap<String, String>,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            filters: STATUS_OPTIONS[1..].iter().map(|s| s.to_string()).collect(),
+            colors: BTreeMap::new(),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/systemdview/config.toml`, usually `~/.config/systemdview/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("systemdview").join("config.toml"))
+}
+
+impl Config {
+    /// Reads and validates a config file; a missing file gives the defaults.
+    ///
+    /// Invalid settings fall back to their defaults and are reported in the
+    /// returned messages, so a broken config never keeps the app from starting.
+    pub fn load(path: &Path) -> (Self, Vec<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return (Self::default(), Vec::new()),
+            Err(e) => {
+                return (
+                    Self::default(),
+                    vec![format!("Failed to read {}: {}", path.display(), e)],
+                );
+            }
+        };
+        match Self::parse(&text) {
+            Ok((config, errors)) => (config, errors),
+            Err(e) => (Self::default(), vec![format!("{}: {}", path.display(), e)]),
+        }
+    }
+
+    /// Parses TOML, returning the validated config and a message per rejected setting
+    pub fn parse(text: &str) -> Result<(Self, Vec<String>), String> {
+        let mut config: Config = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        let errors = config.validate();
+        Ok((config, errors))
+    }
+
+    fn validate(&mut self) -> Vec<String> {
+        let defaults = Config::default();
+        let (_, mut errors) = Keymap::new(&self.keys);
+
+        if !(10..=90).contains(&self.layout.list_width) {
+            errors.push(format!(
+                "layout.list_width must be between 10 and 90, got {}",
+                self.layout.list_width
+            ));
+            self.layout.list_width = defaults.layout.list_width;
+        }
+        if !(1..=MAX_LOG_ENTRIES).contains(&self.logs.lines) {
+            errors.push(format!(
+                "logs.lines must be between 1 and {}, got {}",
+                MAX_LOG_ENTRIES, self.logs.lines
+            ));
+            self.logs.lines = defaults.logs.lines;
+        }
+        if self.status.filters.iter().any(|f| f == "All") {
+            errors.push("status.filters must not list \"All\", it is always offered".to_string());
+            self.status.filters.retain(|f| f != "All");
+        }
+        self.status.colors.retain(|sub, color| {
+            let valid = Color::from_str(color).is_ok();
+            if !valid {
+                errors.push(format!("status.colors.{}: unknown colour \"{}\"", sub, color));
+            }
+            valid
+        });
+        if let Some(ref status) = self.startup.status
+            && !self.status.filters.contains(status)
+        {
+            errors.push(format!(
+                "startup.status \"{}\" is not one of status.filters",
+                status
+            ));
+            self.startup.status = None;
+        }
+        self.startup.unit_type = self.startup.unit_type.to_lowercase();
+        if !UNIT_TYPE_OPTIONS
+            .iter()
+            .any(|t| t.to_lowercase() == self.startup.unit_type)
+        {
+            errors.push(format!(
+                "startup.unit_type \"{}\" is not a unit type",
+                self.startup.unit_type
+            ));
+            self.startup.unit_type = defaults.startup.unit_type;
+        }
+        errors
+    }
+
+    /// Colour overrides keyed by sub-state
+    pub fn status_colors(&self) -> HashMap<String, Color> {
+        self.status
+            .colors
+            .iter()
+            .filter_map(|(sub, color)| Some((sub.clone(), Color::from_str(color).ok()?)))
+            .collect()
+    }
+
+    /// Sets the app up with these settings, before command-line options are applied
+    pub fn apply<B: SystemdBackend>(&self, app: &mut App<B>) {
+        app.list_width = self.layout.list_width;
+        app.log_page_size = self.logs.lines;
+        app.status_options = self.status.filters.clone();
+        app.status_colors = self.status_colors();
+        app.keymap = Keymap::new(&self.keys).0;
+        app.mouse = self.startup.mouse;
+        app.refresh_interval =
+            (self.startup.refresh > 0).then(|| Duration::from_secs(self.startup.refresh));
+        app.show_logs = self.startup.show_logs;
+        app.search_query = self.startup.search.clone().unwrap_or_default();
+        app.status_filter = self.startup.status.clone();
+        let unit_type = (self.startup.unit_type != "all").then(|| self.startup.unit_type.clone());
+        if unit_type != app.unit_type {
+            app.unit_type = unit_type;
+            app.load_services();
+        }
+        app.update_filter();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{sample, settle};
+
+    #[test]
+    fn empty_config_has_defaults() {
+        let (config, errors) = Config::parse("").unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(config.layout.list_width, LIST_WIDTH);
+        assert_eq!(config.logs.lines, LOG_PAGE_SIZE);
+        assert_eq!(config.status.filters, ["running", "exited", "failed", "dead"]);
+        assert_eq!(config.startup.unit_type, "service");
+    }
+
+    #[test]
+    fn invalid_values_fall_back_to_defaults() {
+        let (config, errors) = Config::parse(
+            r#"
+            [layout]
+            list_width = 95
+
+            [logs]
+            lines = 200
+
+            [startup]
+            status = "waiting"
+            unit_type = "gadget"
+
+            [status.colors]
+            failed = "lightred"
+            dead = "greyish"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            errors,
+            [
+                "layout.list_width must be between 10 and 90, got 95",
+                "status.colors.dead: unknown colour \"greyish\"",
+                "startup.status \"waiting\" is not one of status.filters",
+                "startup.unit_type \"gadget\" is not a unit type",
+            ]
+        );
+        assert_eq!(config.layout.list_width, LIST_WIDTH);
+        assert_eq!(config.logs.lines, 200);
+        assert_eq!(config.startup.status, None);
+        assert_eq!(config.startup.unit_type, "service");
+        assert_eq!(
+            config.status_colors(),
+            HashMap::from([("failed".to_string(), Color::LightRed)])
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = Config::parse("[layout]\nlist_wdith = 30\n").unwrap_err();
+        assert!(error.contains("list_wdith"), "{}", error);
+    }
+
+    #[test]
+    fn config_sets_up_the_app() {
+        let (config, _) = Config::parse(
+            r#"
+            [layout]
+            list_width = 30
+
+            [startup]
+            unit_type = "all"
+            status = "failed"
+            refresh = 10
+
+            [status]
+            filters = ["running", "failed", "waiting"]
+            colors = { waiting = "cyan" }
+            "#,
+        )
+        .unwrap();
+        let mut app = App::new(sample());
+        config.apply(&mut app);
+        settle(&mut app);
+
+        assert_eq!(app.list_width, 30);
+        assert_eq!(app.unit_type, None);
+        assert_eq!(app.refresh_interval, Some(Duration::from_secs(10)));
+        assert_eq!(app.services.len(), 5);
+        assert_eq!(app.filtered_indices.len(), 1);
+
+        let timer = app.services.iter().find(|s| s.sub == "waiting").unwrap();
+        assert_eq!(app.status_color(timer), Color::Cyan);
+
+        app.open_status_picker();
+        assert_eq!(app.status_picker_state.selected(), Some(2));
+        app.status_picker_next();
+        app.status_picker_confirm();
+        assert_eq!(app.status_filter.as_deref(), Some("waiting"));
+    }
+
+    #[test]
+    fn keys_are_validated_and_applied() {
+        let (config, errors) = Config::parse(
+            r#"
+            [keys]
+            quit = "Q"
+            refresh = "q"
+            properties = "Q"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(errors, ["keys.properties: \"Q\" is already used by quit"]);
+
+        let mut app = App::new(sample());
+        config.apply(&mut app);
+        assert_eq!(app.keymap.key("quit"), 'Q');
+        assert_eq!(app.keymap.key("refresh"), 'q');
+        assert_eq!(app.keymap.key("properties"), 'p');
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::zvariant::{DynamicType, OwnedObjectPath, OwnedValue, Type, Value};
+
+use crate::properties::UnitProperties;
+use crate::service::{merge_unit_file_states, Scope, SystemdService};
+use crate::time::format_timestamp;
+
+const DESTINATION: &str = "org.freedesktop.systemd1";
+const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+/// Prefix of unit object paths; the rest is the escaped unit name
+const UNIT_PATH_PREFIX: &str = "/org/freedesktop/systemd1/unit/";
+
+/// One row of `Manager.ListUnits`: name, description, load, active, sub, following,
+/// unit path, job id, job type, job path
+type UnitRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+/// A change to the unit list reported by systemd's bus signals
+#[derive(Debug, Clone)]
+pub enum UnitEvent {
+    /// ActiveState and/or SubState of a loaded unit changed
+    StateChanged {
+        unit: String,
+        active: Option<String>,
+        sub: Option<String>,
+    },
+    /// A unit was loaded (`UnitNew`) or unloaded (`UnitRemoved`)
+    Added(String),
+    Removed(String),
+}
+
+/// A connection to the systemd manager on the system or session bus
+pub struct DbusClient {
+    conn: Connection,
+}
+
+impl DbusClient {
+    /// Connects to the bus of `scope`, failing if systemd does not answer on it
+    pub fn connect(scope: Scope) -> Result<Self, String> {
+        let conn = match scope {
+            Scope::System => Connection::system(),
+            Scope::User => Connection::session(),
+        }
+        .map_err(|e| format!("Failed to connect to D-Bus: {}", e))?;
+        let client = Self { conn };
+        client.call::<_, OwnedValue>(
+            MANAGER_PATH,
+            PROPERTIES_INTERFACE,
+            "Get",
+            &(MANAGER_INTERFACE, "Version"),
+        )?;
+        Ok(client)
+    }
+
+    fn call<B, R>(&self, path: &str, interface: &str, method: &str, body: &B) -> Result<R, String>
+    where
+        B: Serialize + DynamicType,
+        R: DeserializeOwned + Type,
+    {
+        let reply = self
+            .conn
+            .call_method(Some(DESTINATION), path, Some(interface), method, body)
+            .map_err(|e| format!("D-Bus call {} failed: {}", method, e))?;
+        reply
+            .body()
+            .deserialize()
+            .map_err(|e| format!("Failed to decode {} reply: {}", method, e))
+    }
+
+    /// Loaded units plus installed unit files, like `systemctl list-units --all`
+    pub fn list_units(&self, unit_type: Option<&str>) -> Result<Vec<SystemdService>, String> {
+        let suffix = unit_type.map(|t| format!(".{}", t));
+        let matches_type =
+            |unit: &str| suffix.as_deref().is_none_or(|suffix| unit.ends_with(suffix));
+
+        let rows: Vec<UnitRow> = self.call(MANAGER_PATH, MANAGER_INTERFACE, "ListUnits", &())?;
+        let mut services: Vec<SystemdService> = rows
+            .into_iter()
+            .filter(|row| matches_type(&row.0))
+            .map(|(unit, description, load, active, sub, ..)| SystemdService {
+                unit,
+                load,
+                active,
+                sub,
+                description,
+                unit_file_state: None,
+            })
+            .collect();
+        services.sort_by(|a, b| a.unit.cmp(&b.unit));
+
+        // Enablement is best-effort; the list is still useful without it
+        let states: HashMap<String, String> = self
+            .unit_file_states()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(unit, _)| matches_type(unit))
+            .collect();
+        merge_unit_file_states(&mut services, states);
+        Ok(services)
+    }
+
+    /// Maps unit file names to their enablement state
+    fn unit_file_states(&self) -> Result<HashMap<String, String>, String> {
+        let files: Vec<(String, String)> =
+            self.call(MANAGER_PATH, MANAGER_INTERFACE, "ListUnitFiles", &())?;
+        Ok(files
+            .into_iter()
+            .filter_map(|(path, state)| {
+                let name = path.rsplit('/').next()?.to_string();
+                Some((name, state))
+            })
+            .collect())
+    }
+
+    /// Current state of one unit, `None` if systemd no longer knows it
+    pub fn get_unit(&self, unit_name: &str) -> Result<Option<SystemdService>, String> {
+        let path: OwnedObjectPath =
+            match self.call(MANAGER_PATH, MANAGER_INTERFACE, "GetUnit", &(unit_name,)) {
+                Ok(path) => path,
+                // Not loaded; it may still have a unit file
+                Err(_) => {
+                    return Ok(self
+                        .unit_file_state(unit_name)
+                        .ok()
+                        .map(|state| SystemdService::unloaded(unit_name.to_string(), state)));
+                }
+            };
+
+        let properties: HashMap<String, OwnedValue> =
+            self.call(path.as_str(), PROPERTIES_INTERFACE, "GetAll", &(UNIT_INTERFACE,))?;
+        let text = |key: &str| properties.get(key).map(|v| value_text(key, v)).unwrap_or_default();
+        let unit_file_state = text("UnitFileState");
+        Ok(Some(SystemdService {
+            unit: unit_name.to_string(),
+            load: text("LoadState"),
+            active: text("ActiveState"),
+            sub: text("SubState"),
+            description: text("Description"),
+            unit_file_state: (!unit_file_state.is_empty()).then_some(unit_file_state),
+        }))
+    }
+
+    /// Enablement of a unit file, e.g. "enabled"
+    fn unit_file_state(&self, unit_name: &str) -> Result<String, String> {
+        self.call(MANAGER_PATH, MANAGER_INTERFACE, "GetUnitFileState", &(unit_name,))
+    }
+
+    /// Every property of every interface of the unit, like `systemctl show --all`
+    pub fn properties(&self, unit_name: &str) -> Result<UnitProperties, String> {
+        // GetUnit rather than LoadUnit, which would load the unit into the manager as a
+        // side effect; of a unit that is not loaded only the unit file is known
+        let path: OwnedObjectPath =
+            match self.call(MANAGER_PATH, MANAGER_INTERFACE, "GetUnit", &(unit_name,)) {
+                Ok(path) => path,
+                Err(_) => {
+                    let state = self.unit_file_state(unit_name)?;
+                    return Ok(UnitProperties::parse(&unloaded_properties(unit_name, &state)));
+                }
+            };
+        let properties: HashMap<String, OwnedValue> =
+            self.call(path.as_str(), PROPERTIES_INTERFACE, "GetAll", &("",))?;
+
+        let mut lines: Vec<String> = properties
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value_text(key, value)))
+            .collect();
+        lines.sort();
+        Ok(UnitProperties::parse(&lines.join("\n")))
+    }
+
+    /// Asks systemd to emit unit signals and streams them from a background thread
+    pub fn subscribe(&self) -> Result<Receiver<UnitEvent>, String> {
+        let rule = format!(
+            "type='signal',sender='{}',path_namespace='{}'",
+            DESTINATION, MANAGER_PATH
+        );
+        let messages = MessageIterator::for_match_rule(rule.as_str(), &self.conn, None)
+            .map_err(|e| format!("Failed to listen for D-Bus signals: {}", e))?;
+        self.call::<_, ()>(MANAGER_PATH, MANAGER_INTERFACE, "Subscribe", &())?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for message in messages {
+                let Ok(message) = message else { continue };
+                let Some(event) = unit_event(&message) else {
+                    continue;
+                };
+                // Stop once the app dropped the receiver
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+}
+
+/// What `systemctl show` reports for a unit that is not loaded, sorted like `properties`
+fn unloaded_properties(unit_name: &str, unit_file_state: &str) -> String {
+    format!(
+        "ActiveState=inactive\nId={}\nLoadState=not-loaded\nSubState=dead\nUnitFileState={}",
+        unit_name, unit_file_state
+    )
+}
+
+fn unit_event(message: &zbus::Message) -> Option<UnitEvent> {
+    let header = message.header();
+    let body = message.body();
+    match header.member()?.as_str() {
+        "UnitNew" => {
+            let (unit, _): (String, OwnedObjectPath) = body.deserialize().ok()?;
+            Some(UnitEvent::Added(unit))
+        }
+        "UnitRemoved" => {
+            let (unit, _): (String, OwnedObjectPath) = body.deserialize().ok()?;
+            Some(UnitEvent::Removed(unit))
+        }
+        "PropertiesChanged" => {
+            let (interface, changed, _): (String, HashMap<String, OwnedValue>, Vec<String>) =
+                body.deserialize().ok()?;
+            if interface != UNIT_INTERFACE {
+                return None;
+            }
+            let active = changed.get("ActiveState").map(|v| value_text("ActiveState", v));
+            let sub = changed.get("SubState").map(|v| value_text("SubState", v));
+            if active.is_none() && sub.is_none() {
+                return None;
+            }
+            let unit = unit_name_from_path(header.path()?.as_str())?;
+            Some(UnitEvent::StateChanged { unit, active, sub })
+        }
+        _ => None,
+    }
+}
+
+/// Reverses systemd's object path escaping, e.g. "sshd_2eservice" -> "sshd.service"
+fn unit_name_from_path(path: &str) -> Option<String> {
+    let escaped = path.strip_prefix(UNIT_PATH_PREFIX)?;
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut rest = escaped.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'_' && tail.len() >= 2 {
+            // from_str_radix alone would also take a sign such as "+1"
+            if !tail[..2].iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            let hex = std::str::from_utf8(&tail[..2]).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Formats a property the way `systemctl show` prints it
+fn value_text(key: &str, value: &Value) -> String {
+    match value {
+        Value::Value(inner) => value_text(key, inner),
+        Value::Str(s) => s.to_string(),
+        Value::ObjectPath(p) => p.to_string(),
+        Value::Bool(b) => if *b { "yes" } else { "no" }.to_string(),
+        // Realtime timestamps are microseconds since the epoch, 0 when unset
+        Value::U64(0) if key.ends_with("Timestamp") => String::new(),
+        Value::U64(usec) if key.ends_with("Timestamp") => format_timestamp(*usec),
+        Value::U8(n) => n.to_string(),
+        Value::I16(n) => n.to_string(),
+        Value::U16(n) => n.to_string(),
+        Value::I32(n) => n.to_string(),
+        Value::U32(n) => n.to_string(),
+        Value::I64(n) => n.to_string(),
+        Value::U64(n) => n.to_string(),
+        Value::F64(n) => n.to_string(),
+        Value::Array(items) => items
+            .inner()
+            .iter()
+            .map(|item| value_text(key, item))
+            .collect::<Vec<_>>()
+            .join(" "),
+        Value::Structure(fields) => format!(
+            "{{ {} }}",
+            fields
+                .fields()
+                .iter()
+                .map(|field| value_text(key, field))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(escaped: &str) -> String {
+        format!("{}{}", UNIT_PATH_PREFIX, escaped)
+    }
+
+    #[test]
+    fn unit_names_are_unescaped_from_object_paths() {
+        let name = |escaped: &str| unit_name_from_path(&path(escaped));
+        assert_eq!(name("sshd_2eservice").as_deref(), Some("sshd.service"));
+        assert_eq!(
+            name("systemd_2djournald_2eservice").as_deref(),
+            Some("systemd-journald.service")
+        );
+        assert_eq!(
+            name("getty_40tty1_2eservice").as_deref(),
+            Some("getty@tty1.service")
+        );
+        // A trailing underscore too short to be an escape is kept
+        assert_eq!(name("odd_").as_deref(), Some("odd_"));
+    }
+
+    #[test]
+    fn malformed_object_paths_are_rejected() {
+        assert_eq!(unit_name_from_path("/org/freedesktop/systemd1/job/42"), None);
+        assert_eq!(unit_name_from_path(&path("bad_zzservice")), None);
+        assert_eq!(unit_name_from_path(&path("bad_+1service")), None);
+        // Escapes must decode to UTF-8
+        assert_eq!(unit_name_from_path(&path("bad_ff_2eservice")), None);
+    }
+
+    #[test]
+    fn values_are_formatted_like_systemctl_show() {
+        assert_eq!(value_text("Id", &Value::from("cron.service")), "cron.service");
+        assert_eq!(value_text("CanStart", &Value::Bool(true)), "yes");
+        assert_eq!(value_text("CanReload", &Value::Bool(false)), "no");
+        assert_eq!(value_text("MainPID", &Value::U32(812)), "812");
+        assert_eq!(value_text("NRestarts", &Value::U64(3)), "3");
+        assert_eq!(
+            value_text("Wants", &Value::from(vec!["a.service", "b.target"])),
+            "a.service b.target"
+        );
+        assert_eq!(
+            value_text("Id", &Value::Value(Box::new(Value::from("nested")))),
+            "nested"
+        );
+    }
+
+    #[test]
+    fn timestamps_are_formatted_and_zero_means_unset() {
+        assert_eq!(value_text("ActiveEnterTimestamp", &Value::U64(0)), "");
+        let usec = 1_700_000_000_000_000;
+        assert_eq!(
+            value_text("ActiveEnterTimestamp", &Value::U64(usec)),
+            format_timestamp(usec)
+        );
+        // Only realtime timestamps are converted
+        assert_eq!(value_text("RuntimeMaxUSec", &Value::U64(usec)), usec.to_string());
+    }
+
+    #[test]
+    fn unloaded_units_report_their_unit_file() {
+        let properties = UnitProperties::parse(&unloaded_properties("old.service", "disabled"));
+        let lines = properties.to_lines().join("\n");
+        assert!(lines.contains("not-loaded"), "{}", lines);
+        assert!(lines.contains("disabled"), "{}", lines);
+    }
+}
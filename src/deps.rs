@@ -0,0 +1,246 @@
+use crate::service::SystemdService;
+
+/// Which edges of the dependency graph to follow, like `systemctl list-dependencies`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyDirection {
+    /// What this unit pulls in
+    Requires,
+    /// What pulls this unit in (`--reverse`)
+    RequiredBy,
+    /// What this unit is ordered after (`--after`)
+    After,
+    /// What this unit is ordered before (`--before`)
+    Before,
+}
+
+impl DependencyDirection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DependencyDirection::Requires => "Requires/Wants",
+            DependencyDirection::RequiredBy => "RequiredBy/WantedBy",
+            DependencyDirection::After => "After",
+            DependencyDirection::Before => "Before",
+        }
+    }
+
+    /// `systemctl show` properties whose unit lists make up the edges
+    pub fn properties(&self) -> &'static [&'static str] {
+        match self {
+            DependencyDirection::Requires => &[
+                "Requires",
+                "Requisite",
+                "Wants",
+                "BindsTo",
+                "Upholds",
+                "ConsistsOf",
+            ],
+            DependencyDirection::RequiredBy => &[
+                "RequiredBy",
+                "RequisiteOf",
+                "WantedBy",
+                "BoundBy",
+                "UpheldBy",
+                "PartOf",
+            ],
+            DependencyDirection::After => &["After"],
+            DependencyDirection::Before => &["Before"],
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            DependencyDirection::Requires => DependencyDirection::RequiredBy,
+            DependencyDirection::RequiredBy => DependencyDirection::After,
+            DependencyDirection::After => DependencyDirection::Before,
+            DependencyDirection::Before => DependencyDirection::Requires,
+        }
+    }
+}
+
+pub struct DepNode {
+    pub service: SystemdService,
+    pub depth: usize,
+    pub expanded: bool,
+    /// The unit already appears above this node on its own path
+    pub cycle: bool,
+}
+
+/// A dependency tree stored as its visible rows in display order;
+/// expanding inserts children after a node and collapsing removes them
+pub struct DependencyTree {
+    pub direction: DependencyDirection,
+    pub nodes: Vec<DepNode>,
+}
+
+impl DependencyTree {
+    pub fn new(root: SystemdService, direction: DependencyDirection) -> Self {
+        Self {
+            direction,
+            nodes: vec![DepNode {
+                service: root,
+                depth: 0,
+                expanded: false,
+                cycle: false,
+            }],
+        }
+    }
+
+    pub fn root(&self) -> &SystemdService {
+        &self.nodes[0].service
+    }
+
+    /// Units on the path from the root down to (and including) the node at `index`
+    fn path_to(&self, index: usize) -> Vec<&str> {
+        let mut path = vec![self.nodes[index].service.unit.as_str()];
+        let mut depth = self.nodes[index].depth;
+        for node in self.nodes[..index].iter().rev() {
+            if node.depth < depth {
+                path.push(&node.service.unit);
+                depth = node.depth;
+            }
+        }
+        path
+    }
+
+    /// Shows `children` below the node at `index`
+    pub fn expand(&mut self, index: usize, children: Vec<SystemdService>) {
+        if self.nodes[index].expanded {
+            return;
+        }
+        let depth = self.nodes[index].depth + 1;
+        let path: Vec<String> = self
+            .path_to(index)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let rows: Vec<DepNode> = children
+            .into_iter()
+            .map(|service| DepNode {
+                cycle: path.contains(&service.unit),
+                service,
+                depth,
+                expanded: false,
+            })
+            .collect();
+        self.nodes[index].expanded = true;
+        self.nodes.splice(index + 1..index + 1, rows);
+    }
+
+    /// Hides everything below the node at `index`
+    pub fn collapse(&mut self, index: usize) {
+        let depth = self.nodes[index].depth;
+        let end = self.nodes[index + 1..]
+            .iter()
+            .position(|n| n.depth <= depth)
+            .map_or(self.nodes.len(), |p| index + 1 + p);
+        self.nodes.drain(index + 1..end);
+        self.nodes[index].expanded = false;
+    }
+
+    /// Index of the closest node above `index` with a smaller depth
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        let depth = self.nodes[index].depth;
+        self.nodes[..index].iter().rposition(|n| n.depth < depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::unit;
+
+    fn units(names: &[&str]) -> Vec<SystemdService> {
+        names
+            .iter()
+            .map(|name| unit(name, "active", "running", ""))
+            .collect()
+    }
+
+    fn rows(tree: &DependencyTree) -> Vec<(usize, &str)> {
+        tree.nodes
+            .iter()
+            .map(|n| (n.depth, n.service.unit.as_str()))
+            .collect()
+    }
+
+    /// a.service with b and c below it, and d below b
+    fn tree() -> DependencyTree {
+        let mut tree = DependencyTree::new(
+            unit("a.service", "active", "running", ""),
+            DependencyDirection::Requires,
+        );
+        tree.expand(0, units(&["b.service", "c.service"]));
+        tree.expand(1, units(&["d.service"]));
+        tree
+    }
+
+    #[test]
+    fn expand_inserts_children_below_the_node() {
+        let tree = tree();
+        assert_eq!(
+            rows(&tree),
+            [(0, "a.service"), (1, "b.service"), (2, "d.service"), (1, "c.service")]
+        );
+        assert!(tree.nodes[0].expanded && tree.nodes[1].expanded);
+        assert!(!tree.nodes[3].expanded);
+    }
+
+    #[test]
+    fn expanding_twice_adds_nothing() {
+        let mut tree = tree();
+        tree.expand(1, units(&["e.service"]));
+        assert_eq!(tree.nodes.len(), 4);
+    }
+
+    #[test]
+    fn collapse_removes_the_whole_subtree() {
+        let mut root = tree();
+        root.collapse(0);
+        assert_eq!(rows(&root), [(0, "a.service")]);
+        assert!(!root.nodes[0].expanded);
+
+        let mut branch = tree();
+        branch.collapse(1);
+        assert_eq!(rows(&branch), [(0, "a.service"), (1, "b.service"), (1, "c.service")]);
+        assert!(!branch.nodes[1].expanded);
+    }
+
+    #[test]
+    fn parent_of_nested_nodes() {
+        let tree = tree();
+        assert_eq!(tree.parent(0), None);
+        assert_eq!(tree.parent(1), Some(0));
+        assert_eq!(tree.parent(2), Some(1));
+        // c follows b's subtree but hangs off the root
+        assert_eq!(tree.parent(3), Some(0));
+    }
+
+    #[test]
+    fn a_unit_below_itself_is_a_cycle() {
+        let mut tree = DependencyTree::new(
+            unit("a.service", "active", "running", ""),
+            DependencyDirection::After,
+        );
+        tree.expand(0, units(&["b.service"]));
+        tree.expand(1, units(&["a.service", "c.service"]));
+        assert_eq!(
+            rows(&tree),
+            [(0, "a.service"), (1, "b.service"), (2, "a.service"), (2, "c.service")]
+        );
+        assert!(tree.nodes[2].cycle);
+        assert!(!tree.nodes[3].cycle);
+
+        // Further down, any ancestor counts, not just the root
+        tree.expand(3, units(&["b.service", "d.service"]));
+        assert!(tree.nodes[4].cycle);
+        assert!(!tree.nodes[5].cycle);
+    }
+
+    #[test]
+    fn units_from_other_branches_are_not_cycles() {
+        let mut tree = tree();
+        tree.expand(2, units(&["c.service"]));
+        assert_eq!(rows(&tree)[3], (3, "c.service"));
+        assert!(!tree.nodes[3].cycle);
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::service::Scope;
+
+/// Sections systemd accepts in unit files; anything starting with "X-" is also allowed
+const KNOWN_SECTIONS: [&str; 11] = [
+    "Unit",
+    "Install",
+    "Service",
+    "Socket",
+    "Timer",
+    "Mount",
+    "Automount",
+    "Swap",
+    "Path",
+    "Slice",
+    "Scope",
+];
+
+/// Location of the `override.conf` drop-in for a unit, as `systemctl edit` uses it
+pub fn override_path(scope: Scope, unit_name: &str) -> PathBuf {
+    let base = match scope {
+        Scope::System => PathBuf::from("/etc/systemd/system"),
+        Scope::User => std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .unwrap_or_else(|| PathBuf::from(".config"))
+            .join("systemd/user"),
+    };
+    base.join(format!("{}.d", unit_name)).join("override.conf")
+}
+
+/// Initial contents offered in the editor when the unit has no override yet
+pub fn template(unit_name: &str) -> String {
+    format!(
+        "# Override for {}\n\
+         # Settings here are applied on top of the unit file, e.g.\n\
+         #\n\
+         # [Service]\n\
+         # Restart=always\n",
+        unit_name
+    )
+}
+
+/// Whether the drop-in contains nothing but comments and blank lines
+pub fn is_empty(content: &str) -> bool {
+    content.lines().all(|line| {
+        let line = line.trim();
+        line.is_empty() || line.starts_with('#') || line.starts_with(';')
+    })
+}
+
+/// Checks unit file syntax: every setting must be a Key=Value inside a known section
+pub fn validate(content: &str) -> Result<(), String> {
+    let mut in_section = false;
+    let mut continuation = false;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw.trim();
+
+        if continuation {
+            continuation = line.ends_with('\\');
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) else {
+                return Err(format!("line {}: unterminated section header", line_no));
+            };
+            if !KNOWN_SECTIONS.contains(&name) && !name.starts_with("X-") {
+                return Err(format!("line {}: unknown section [{}]", line_no, name));
+            }
+            in_section = true;
+            continue;
+        }
+
+        let Some((key, _)) = line.split_once('=') else {
+            return Err(format!("line {}: expected Key=Value", line_no));
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return Err(format!("line {}: invalid key \"{}\"", line_no, key));
+        }
+        if !in_section {
+            return Err(format!(
+                "line {}: {} is outside of any section",
+                line_no, key
+            ));
+        }
+        continuation = line.ends_with('\\');
+    }
+
+    Ok(())
+}
+
+/// Atomically replaces the override; an empty drop-in removes it instead
+pub fn install(scope: Scope, unit_name: &str, content: &str) -> io::Result<PathBuf> {
+    let path = override_path(scope, unit_name);
+
+    if is_empty(content) {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        if let Some(dir) = path.parent() {
+            // Only succeeds if nothing else lives in the drop-in directory
+            let _ = fs::remove_dir(dir);
+        }
+        return Ok(path);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("conf.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_settings_in_known_sections() {
+        let content = "# Override for cron.service\n\
+                       \n\
+                       [Service]\n\
+                       Restart=always\n\
+                       ; an alternative comment style\n\
+                       ExecStart=\n\
+                       ExecStart=/usr/sbin/cron -f \\\n\
+                       \x20   -L 15\n\
+                       \n\
+                       [X-Custom]\n\
+                       Some.Key_name-2 = value\n";
+        assert_eq!(validate(content), Ok(()));
+        assert_eq!(validate(&template("cron.service")), Ok(()));
+        assert_eq!(validate(""), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_offending_line() {
+        assert_eq!(
+            validate("Restart=always\n"),
+            Err("line 1: Restart is outside of any section".to_string())
+        );
+        assert_eq!(
+            validate("[Service\nRestart=always\n"),
+            Err("line 1: unterminated section header".to_string())
+        );
+        assert_eq!(
+            validate("[Service]\n\n[Servise]\n"),
+            Err("line 3: unknown section [Servise]".to_string())
+        );
+        assert_eq!(
+            validate("[Service]\nRestart always\n"),
+            Err("line 2: expected Key=Value".to_string())
+        );
+        assert_eq!(
+            validate("[Service]\nRe start=always\n"),
+            Err("line 2: invalid key \"Re start\"".to_string())
+        );
+        assert_eq!(
+            validate("[Service]\n=always\n"),
+            Err("line 2: invalid key \"\"".to_string())
+        );
+    }
+
+    #[test]
+    fn continuation_lines_are_not_parsed_as_settings() {
+        assert_eq!(
+            validate("[Service]\nExecStart=/bin/true \\\n  not a setting\nRestart=no\n"),
+            Ok(())
+        );
+        assert_eq!(
+            validate("[Service]\nExecStart=/bin/true\n  not a setting\n"),
+            Err("line 3: expected Key=Value".to_string())
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::service::Scope;
+use crate::time::format_local;
+
+/// A column of the logs panel, taken from a journal field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogColumn {
+    Time,
+    Priority,
+    Hostname,
+    Unit,
+    Identifier,
+    Pid,
+    Message,
+}
+
+impl LogColumn {
+    pub const ALL: [LogColumn; 7] = [
+        LogColumn::Time,
+        LogColumn::Priority,
+        LogColumn::Hostname,
+        LogColumn::Unit,
+        LogColumn::Identifier,
+        LogColumn::Pid,
+        LogColumn::Message,
+    ];
+
+    /// Matches journalctl's default `short` output
+    pub const DEFAULT: [LogColumn; 5] = [
+        LogColumn::Time,
+        LogColumn::Hostname,
+        LogColumn::Identifier,
+        LogColumn::Pid,
+        LogColumn::Message,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogColumn::Time => "Time",
+            LogColumn::Priority => "Priority",
+            LogColumn::Hostname => "Hostname",
+            LogColumn::Unit => "Unit",
+            LogColumn::Identifier => "Identifier",
+            LogColumn::Pid => "PID",
+            LogColumn::Message => "Message",
+        }
+    }
+}
+
+/// Syslog priority names, indexed by level
+pub const PRIORITY_NAMES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+/// One journal record from `journalctl --output=json`
+#[derive(Debug, Clone, Default)]
+pub struct JournalEntry {
+    pub realtime_usec: Option<u64>,
+    pub priority: Option<u8>,
+    pub pid: Option<u32>,
+    pub identifier: Option<String>,
+    pub hostname: Option<String>,
+    pub unit: Option<String>,
+    pub message: String,
+    /// Position in the journal, used to page to older or newer entries
+    pub cursor: Option<String>,
+    /// Every field of the record, as text
+    pub fields: BTreeMap<String, String>,
+}
+
+impl JournalEntry {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let object: BTreeMap<String, Value> =
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let fields: BTreeMap<String, String> = object
+            .into_iter()
+            .map(|(key, value)| (key, field_text(value)))
+            .collect();
+        let text = |key: &str| fields.get(key).filter(|v| !v.is_empty()).cloned();
+
+        Ok(Self {
+            realtime_usec: text("__REALTIME_TIMESTAMP").and_then(|v| v.parse().ok()),
+            priority: text("PRIORITY").and_then(|v| v.parse().ok()),
+            pid: text("_PID")
+                .or_else(|| text("SYSLOG_PID"))
+                .and_then(|v| v.parse().ok()),
+            identifier: text("SYSLOG_IDENTIFIER").or_else(|| text("_COMM")),
+            hostname: text("_HOSTNAME"),
+            unit: text("_SYSTEMD_UNIT").or_else(|| text("_SYSTEMD_USER_UNIT")),
+            message: text("MESSAGE").unwrap_or_default(),
+            cursor: text("__CURSOR"),
+            fields,
+        })
+    }
+
+    /// A pseudo-entry carrying only a message, used to surface errors in the panel
+    pub fn from_message(message: String) -> Self {
+        Self {
+            message,
+            ..Self::default()
+        }
+    }
+
+    pub fn priority_name(&self) -> Option<&'static str> {
+        self.priority
+            .and_then(|p| PRIORITY_NAMES.get(p as usize).copied())
+    }
+
+    /// Renders the entry as one panel line with the chosen columns
+    pub fn format(&self, columns: &[LogColumn]) -> String {
+        let mut parts = Vec::new();
+        for column in columns {
+            let part = match column {
+                LogColumn::Time => self.realtime_usec.map(|usec| format_local(usec, "%b %d %H:%M:%S")),
+                LogColumn::Priority => self.priority_name().map(|p| format!("<{}>", p)),
+                LogColumn::Hostname => self.hostname.clone(),
+                LogColumn::Unit => self.unit.clone(),
+                LogColumn::Identifier => self.identifier.as_ref().map(|ident| {
+                    match self.pid.filter(|_| columns.contains(&LogColumn::Pid)) {
+                        Some(pid) => format!("{}[{}]:", ident, pid),
+                        None => format!("{}:", ident),
+                    }
+                }),
+                // Shown with the identifier when both are enabled
+                LogColumn::Pid if columns.contains(&LogColumn::Identifier) => None,
+                LogColumn::Pid => self.pid.map(|pid| format!("[{}]", pid)),
+                LogColumn::Message => Some(self.message.clone()),
+            };
+            if let Some(part) = part {
+                parts.push(part);
+            }
+        }
+        parts.join(" ")
+    }
+}
+
+/// Journal field values are strings, byte arrays for binary data, or null
+fn field_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        Value::Null => String::new(),
+        Value::Array(items) if items.iter().all(Value::is_u64) => {
+            let bytes: Vec<u8> = items
+                .iter()
+                .filter_map(Value::as_u64)
+                .map(|b| b as u8)
+                .collect();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        // Fields that occur several times in one entry
+        Value::Array(items) => items
+            .into_iter()
+            .map(field_text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+/// Which journal files journalctl reads
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum JournalSource {
+    /// The journal of the running system
+    #[default]
+    Local,
+    /// A journal directory, e.g. /var/log/journal copied from another machine (`--directory`)
+    Directory(PathBuf),
+    /// Individual journal files (`--file`)
+    Files(Vec<PathBuf>),
+}
+
+impl JournalSource {
+    /// A journalctl invocation reading from this source
+    fn command(&self) -> Command {
+        let mut cmd = Command::new("journalctl");
+        match self {
+            JournalSource::Local => {}
+            JournalSource::Directory(dir) => {
+                cmd.arg("--directory").arg(dir);
+            }
+            JournalSource::Files(files) => {
+                for file in files {
+                    cmd.arg("--file").arg(file);
+                }
+            }
+        }
+        cmd
+    }
+}
+
+/// What to read from the journal for the logs panel
+#[derive(Debug, Clone)]
+pub struct LogQuery {
+    pub source: JournalSource,
+    pub scope: Scope,
+    pub unit: String,
+    pub lines: usize,
+    /// Only entries at this syslog level or more severe (`journalctl -p`)
+    pub priority: Option<u8>,
+    /// Any timestamp or relative expression journalctl accepts, e.g. "-1h" or "yesterday"
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Boot offset (`journalctl -b`), 0 being the current boot
+    pub boot: Option<i32>,
+}
+
+impl LogQuery {
+    fn command(&self) -> Command {
+        let mut cmd = self.source.command();
+        cmd.args([
+            self.scope.journal_unit_flag(),
+            &self.unit,
+            "--output=json",
+            "--no-pager",
+        ]);
+        if let Some(priority) = self.priority {
+            cmd.args(["-p", &priority.to_string()]);
+        }
+        if let Some(ref since) = self.since {
+            cmd.arg(format!("--since={}", since));
+        }
+        if let Some(ref until) = self.until {
+            cmd.arg(format!("--until={}", until));
+        }
+        if let Some(boot) = self.boot {
+            cmd.arg(format!("--boot={}", boot));
+        }
+        cmd
+    }
+}
+
+/// Which side of the loaded entries a page is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageDirection {
+    Older,
+    Newer,
+}
+
+/// The newest `query.lines` entries, oldest first
+pub fn fetch_logs(query: &LogQuery) -> Result<Vec<JournalEntry>, String> {
+    let mut cmd = query.command();
+    cmd.args(["-n", &query.lines.to_string()]);
+    run_journalctl(cmd)
+}
+
+/// Up to `query.lines` entries directly before or after `cursor`, oldest first
+pub fn fetch_log_page(
+    query: &LogQuery,
+    cursor: &str,
+    direction: PageDirection,
+) -> Result<Vec<JournalEntry>, String> {
+    let mut cmd = query.command();
+    // journalctl has no --before-cursor; reading in reverse from the cursor is the equivalent
+    cmd.arg(format!("--after-cursor={}", cursor))
+        .args(["-n", &query.lines.to_string()]);
+    if direction == PageDirection::Older {
+        cmd.arg("--reverse");
+    }
+    let mut entries = run_journalctl(cmd)?;
+    if direction == PageDirection::Older {
+        entries.reverse();
+    }
+    Ok(entries)
+}
+
+fn run_journalctl(mut cmd: Command) -> Result<Vec<JournalEntry>, String> {
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to execute journalctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(JournalEntry::parse)
+        .collect()
+}
+
+/// One boot as listed by `journalctl --list-boots`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Boot {
+    /// Offset relative to the current boot: 0, -1, -2, ...
+    pub index: i32,
+    pub boot_id: String,
+    /// Realtime of the first and last entries in microseconds
+    pub first_entry: u64,
+    pub last_entry: u64,
+}
+
+/// Label for a `-b` offset, e.g. "current boot" or "boot -1"
+pub fn boot_label(offset: i32) -> String {
+    if offset == 0 {
+        "current boot".to_string()
+    } else {
+        format!("boot {}", offset)
+    }
+}
+
+/// Boots recorded in the journal, most recent first
+pub fn fetch_boots(source: &JournalSource) -> Result<Vec<Boot>, String> {
+    let output = source
+        .command()
+        .args(["--list-boots", "--output=json", "--no-pager"])
+        .output()
+        .map_err(|e| format!("Failed to execute journalctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let mut boots: Vec<Boot> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse boot list: {}", e))?;
+    boots.sort_by_key(|boot| std::cmp::Reverse(boot.index));
+    Ok(boots)
+}
+
+/// Names of the units that have entries in the journal, sorted
+pub fn fetch_journal_units(source: &JournalSource, scope: Scope) -> Result<Vec<String>, String> {
+    let field = match scope {
+        Scope::System => "_SYSTEMD_UNIT",
+        Scope::User => "_SYSTEMD_USER_UNIT",
+    };
+    let output = source
+        .command()
+        .arg(format!("--field={}", field))
+        .output()
+        .map_err(|e| format!("Failed to execute journalctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let mut units: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    units.sort();
+    Ok(units)
+}
+
+/// A long-running `journalctl -f` whose entries arrive on a channel
+pub struct LogFollower {
+    child: Option<Child>,
+    entries: Receiver<JournalEntry>,
+}
+
+impl LogFollower {
+    /// Starts following new entries of a unit, from the entry after `after_cursor`
+    /// if given so nothing written since that entry is missed, else from now
+    pub fn spawn(query: &LogQuery, after_cursor: Option<&str>) -> Result<Self, String> {
+        let mut cmd = query.command();
+        match after_cursor {
+            Some(cursor) => cmd.args(["-f", "-n", "all"]).arg(format!("--after-cursor={}", cursor)),
+            None => cmd.args(["-f", "-n", "0"]),
+        };
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to execute journalctl: {}", e))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "Failed to capture journalctl output".to_string())?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                // Stop once the process is gone or the follower was dropped
+                let Ok(line) = line else { break };
+                let Ok(entry) = JournalEntry::parse(&line) else {
+                    continue;
+                };
+                if tx.send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child: Some(child),
+            entries: rx,
+        })
+    }
+
+    /// A follower fed by something other than journalctl
+    #[cfg(test)]
+    pub fn from_channel(entries: Receiver<JournalEntry>) -> Self {
+        Self {
+            child: None,
+            entries,
+        }
+    }
+
+    /// Entries received since the last call, without blocking
+    pub fn drain(&self) -> Vec<JournalEntry> {
+        self.entries.try_iter().collect()
+    }
+}
+
+impl Drop for LogFollower {
+    fn drop(&mut self) {
+        if let Some(ref mut child) = self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args().map(|a| a.to_string_lossy().into_owned()).collect()
+    }
+
+    #[test]
+    fn queries_read_from_the_journal_source() {
+        let mut query = LogQuery {
+            source: JournalSource::Local,
+            scope: Scope::System,
+            unit: "ssh.service".to_string(),
+            lines: 100,
+            priority: None,
+            since: None,
+            until: None,
+            boot: None,
+        };
+        assert!(!args(&query.command()).iter().any(|a| a.starts_with("--directory")));
+
+        query.source = JournalSource::Directory(PathBuf::from("/mnt/journal"));
+        assert_eq!(args(&query.command())[..2], ["--directory", "/mnt/journal"]);
+
+        query.source = JournalSource::Files(vec![
+            PathBuf::from("system.journal"),
+            PathBuf::from("user-1000.journal"),
+        ]);
+        assert_eq!(
+            args(&query.command())[..4],
+            ["--file", "system.journal", "--file", "user-1000.journal"]
+        );
+    }
+}
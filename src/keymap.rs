@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::KeyCode;
+
+/// Where a key is pressed; the same key may do different things in each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    /// The unit list, with no side panel open
+    Units,
+    /// The logs, unit file or properties panel
+    Panel,
+    Timers,
+}
+
+use KeyContext::{Panel, Timers, Units};
+
+/// An action whose key can be changed in the `[keys]` config section
+struct Binding {
+    action: &'static str,
+    default: char,
+    contexts: &'static [KeyContext],
+}
+
+const fn bind(action: &'static str, default: char, contexts: &'static [KeyContext]) -> Binding {
+    Binding {
+        action,
+        default,
+        contexts,
+    }
+}
+
+/// Pickers, dialogs and search prompts keep their keys
+const BINDINGS: [Binding; 29] = [
+    bind("quit", 'q', &[Units, Panel, Timers]),
+    bind("down", 'j', &[Units, Panel, Timers]),
+    bind("up", 'k', &[Units, Panel, Timers]),
+    bind("top", 'g', &[Units, Panel, Timers]),
+    bind("bottom", 'G', &[Units, Panel, Timers]),
+    bind("search", '/', &[Units, Panel]),
+    bind("logs", 'l', &[Units, Panel]),
+    bind("refresh", 'r', &[Units, Timers]),
+    bind("timers", 'T', &[Units, Timers]),
+    bind("auto_refresh", 'R', &[Units]),
+    bind("acknowledge", 'x', &[Units]),
+    bind("acknowledge_all", 'X', &[Units]),
+    bind("status_filter", 's', &[Units]),
+    bind("type_filter", 't', &[Units]),
+    bind("actions", 'a', &[Units]),
+    bind("scope", 'u', &[Units]),
+    bind("unit_file", 'c', &[Units]),
+    bind("edit", 'e', &[Units]),
+    bind("properties", 'p', &[Units]),
+    bind("dependencies", 'd', &[Units]),
+    bind("follow", 'f', &[Panel]),
+    bind("columns", 'C', &[Panel]),
+    bind("priority", 'p', &[Panel]),
+    bind("time_range", 't', &[Panel]),
+    bind("boots", 'b', &[Panel]),
+    bind("next_match", 'n', &[Panel]),
+    bind("previous_match", 'N', &[Panel]),
+    bind("copy_line", 'y', &[Panel]),
+    bind("copy_all", 'Y', &[Panel]),
+];
+
+/// Keys of the main views, after the `[keys]` config section is applied
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    /// Actions bound to something other than their default key
+    custom: HashMap<&'static str, char>,
+}
+
+impl Keymap {
+    /// Builds the keymap from `[keys]` settings such as `quit = "Q"`, returning a
+    /// message for each setting that was ignored
+    pub fn new(settings: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut keymap = Keymap::default();
+
+        for (action, value) in settings {
+            let Some(binding) = BINDINGS.iter().find(|b| b.action == action) else {
+                errors.push(format!("keys.{}: unknown action", action));
+                continue;
+            };
+            let mut chars = value.chars();
+            let (Some(key), None) = (chars.next(), chars.next()) else {
+                errors.push(format!(
+                    "keys.{}: expected a single character, got \"{}\"",
+                    action, value
+                ));
+                continue;
+            };
+            if key == '?' {
+                errors.push(format!("keys.{}: \"?\" is reserved for help", action));
+                continue;
+            }
+            if key != binding.default {
+                keymap.custom.insert(binding.action, key);
+            }
+        }
+
+        // A custom key taken by another action falls back to the default, which may in
+        // turn clash with a key moved there, so repeat until nothing changes
+        while let Some((action, other)) = keymap.clash() {
+            errors.push(format!(
+                "keys.{}: \"{}\" is already used by {}",
+                action,
+                keymap.key(action),
+                other
+            ));
+            keymap.custom.remove(action);
+        }
+
+        (keymap, errors)
+    }
+
+    /// A custom binding that shares its key with another action in some context
+    fn clash(&self) -> Option<(&'static str, &'static str)> {
+        let mut custom: Vec<&'static str> = self.custom.keys().copied().collect();
+        custom.sort();
+        custom.into_iter().find_map(|action| {
+            let binding = BINDINGS.iter().find(|b| b.action == action)?;
+            let other = BINDINGS.iter().find(|other| {
+                other.action != action
+                    && self.key(other.action) == self.key(action)
+                    && other.contexts.iter().any(|c| binding.contexts.contains(c))
+            })?;
+            Some((action, other.action))
+        })
+    }
+
+    /// The key an action is bound to
+    pub fn key(&self, action: &str) -> char {
+        self.custom.get(action).copied().unwrap_or_else(|| {
+            BINDINGS
+                .iter()
+                .find(|b| b.action == action)
+                .map_or('\0', |b| b.default)
+        })
+    }
+
+    /// Maps a pressed key to the default key of the action it is bound to, so the
+    /// dispatch can keep matching on defaults. A default key whose action moved
+    /// elsewhere does nothing.
+    pub fn translate(&self, context: KeyContext, code: KeyCode) -> KeyCode {
+        let KeyCode::Char(c) = code else {
+            return code;
+        };
+        let in_context = || BINDINGS.iter().filter(|b| b.contexts.contains(&context));
+        if let Some(binding) = in_context().find(|b| self.key(b.action) == c) {
+            KeyCode::Char(binding.default)
+        } else if in_context().any(|b| b.default == c) {
+            KeyCode::Null
+        } else {
+            code
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(settings: &[(&str, &str)]) -> (Keymap, Vec<String>) {
+        let settings = settings
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect();
+        Keymap::new(&settings)
+    }
+
+    #[test]
+    fn moved_keys_translate_to_their_defaults() {
+        let (keys, errors) = keymap(&[("quit", "Q"), ("follow", "F")]);
+        assert!(errors.is_empty());
+        assert_eq!(keys.translate(Units, KeyCode::Char('Q')), KeyCode::Char('q'));
+        assert_eq!(keys.translate(Units, KeyCode::Char('q')), KeyCode::Null);
+        assert_eq!(keys.translate(Panel, KeyCode::Char('F')), KeyCode::Char('f'));
+        assert_eq!(keys.translate(Panel, KeyCode::Char('f')), KeyCode::Null);
+        // Outside of its context a key keeps its meaning
+        assert_eq!(keys.translate(Units, KeyCode::Char('F')), KeyCode::Char('F'));
+        assert_eq!(keys.translate(Units, KeyCode::Enter), KeyCode::Enter);
+    }
+
+    #[test]
+    fn keys_can_be_swapped() {
+        let (keys, errors) = keymap(&[("down", "k"), ("up", "j")]);
+        assert!(errors.is_empty());
+        assert_eq!(keys.translate(Timers, KeyCode::Char('j')), KeyCode::Char('k'));
+        assert_eq!(keys.translate(Timers, KeyCode::Char('k')), KeyCode::Char('j'));
+    }
+
+    #[test]
+    fn contexts_only_clash_where_they_overlap() {
+        // "t" picks the unit type in the list and the time range in the logs panel
+        let (keys, errors) = keymap(&[("follow", "s"), ("type_filter", "j")]);
+        assert_eq!(errors, ["keys.type_filter: \"j\" is already used by down"]);
+        assert_eq!(keys.key("follow"), 's');
+        assert_eq!(keys.key("type_filter"), 't');
+    }
+
+    #[test]
+    fn invalid_settings_are_reported() {
+        let (keys, errors) =
+            keymap(&[("quit", "ctrl+q"), ("jump", "J"), ("logs", "?"), ("refresh", "")]);
+        assert_eq!(
+            errors,
+            [
+                "keys.jump: unknown action",
+                "keys.logs: \"?\" is reserved for help",
+                "keys.quit: expected a single character, got \"ctrl+q\"",
+                "keys.refresh: expected a single character, got \"\"",
+            ]
+        );
+        assert_eq!(keys.key("quit"), 'q');
+        assert_eq!(keys.key("logs"), 'l');
+    }
+}
@@ -0,0 +1,305 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+use crate::backend::SystemdBackend;
+use crate::dbus::UnitEvent;
+use crate::deps::DependencyDirection;
+use crate::journal::{Boot, JournalEntry, LogQuery, PageDirection};
+use crate::properties::UnitProperties;
+use crate::service::{Scope, ServiceAction, SystemdService, SystemdTimer};
+
+/// A finished background request, tagged with the request it answers
+pub enum Loaded<B> {
+    Services {
+        generation: u64,
+        result: Result<Vec<SystemdService>, String>,
+    },
+    Logs {
+        generation: u64,
+        unit: String,
+        result: Result<Vec<JournalEntry>, String>,
+    },
+    /// Entries directly before or after `cursor`
+    LogPage {
+        generation: u64,
+        unit: String,
+        cursor: String,
+        direction: PageDirection,
+        result: Result<Vec<JournalEntry>, String>,
+    },
+    /// Current state of one unit, e.g. after an action; `generation` is the unit
+    /// list request it was started under
+    Service {
+        generation: u64,
+        unit: String,
+        result: Result<Option<SystemdService>, String>,
+    },
+    UnitFile {
+        generation: u64,
+        result: Result<Vec<String>, String>,
+    },
+    Properties {
+        generation: u64,
+        result: Result<UnitProperties, String>,
+    },
+    Timers {
+        generation: u64,
+        result: Result<Vec<SystemdTimer>, String>,
+    },
+    Boots {
+        generation: u64,
+        result: Result<Vec<Boot>, String>,
+    },
+    /// Children of the dependency tree node at `index`
+    Dependencies {
+        generation: u64,
+        index: usize,
+        unit: String,
+        direction: DependencyDirection,
+        result: Result<Vec<SystemdService>, String>,
+    },
+    Action {
+        unit: String,
+        action: ServiceAction,
+        result: Result<String, String>,
+    },
+    /// A daemon reload after `unit`'s override was saved to `path`
+    Reload {
+        unit: String,
+        path: PathBuf,
+        result: Result<(), String>,
+    },
+    /// The backend connected to another service manager, with its unit signals
+    Scope {
+        generation: u64,
+        backend: B,
+        events: Option<Receiver<UnitEvent>>,
+    },
+}
+
+/// Runs slow systemctl/journalctl/D-Bus calls on worker threads.
+///
+/// Each kind of request has a generation counter; starting a new request
+/// supersedes the previous one, whose result is dropped instead of applied.
+/// Workers that have not started their command yet skip it altogether, but a
+/// command that is already running is left to finish. Actions and reloads
+/// change the system, so they always run and report back.
+pub struct Loader<B> {
+    tx: Sender<Loaded<B>>,
+    rx: Receiver<Loaded<B>>,
+    services: Arc<AtomicU64>,
+    logs: Arc<AtomicU64>,
+    pages: Arc<AtomicU64>,
+    panel: Arc<AtomicU64>,
+    timers: Arc<AtomicU64>,
+    boots: Arc<AtomicU64>,
+    deps: Arc<AtomicU64>,
+    scopes: Arc<AtomicU64>,
+    /// Workers that have not finished yet
+    running: Arc<AtomicUsize>,
+}
+
+impl<B: SystemdBackend> Loader<B> {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            tx,
+            rx,
+            services: Arc::new(AtomicU64::new(0)),
+            logs: Arc::new(AtomicU64::new(0)),
+            pages: Arc::new(AtomicU64::new(0)),
+            panel: Arc::new(AtomicU64::new(0)),
+            timers: Arc::new(AtomicU64::new(0)),
+            boots: Arc::new(AtomicU64::new(0)),
+            deps: Arc::new(AtomicU64::new(0)),
+            scopes: Arc::new(AtomicU64::new(0)),
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn load_services(&self, backend: B, unit_type: Option<String>) {
+        self.spawn(&self.services, move |generation| Loaded::Services {
+            generation,
+            result: backend.fetch_services(unit_type.as_deref()),
+        });
+    }
+
+    /// Loads the newest entries of a unit, superseding any page request as well
+    pub fn load_logs(&self, backend: B, query: LogQuery) {
+        self.pages.fetch_add(1, Ordering::SeqCst);
+        self.spawn(&self.logs, move |generation| Loaded::Logs {
+            generation,
+            result: backend.fetch_logs(&query),
+            unit: query.unit,
+        });
+    }
+
+    pub fn load_log_page(
+        &self,
+        backend: B,
+        query: LogQuery,
+        cursor: String,
+        direction: PageDirection,
+    ) {
+        self.spawn(&self.pages, move |generation| Loaded::LogPage {
+            generation,
+            result: backend.fetch_log_page(&query, &cursor, direction),
+            unit: query.unit,
+            cursor,
+            direction,
+        });
+    }
+
+    /// Marks the pending logs and page requests as superseded, so their results are dropped
+    pub fn discard_logs(&self) {
+        self.logs.fetch_add(1, Ordering::SeqCst);
+        self.pages.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Re-reads one unit. These never supersede each other, but a later unit list
+    /// request or scope switch supersedes them.
+    pub fn load_service(&self, backend: B, unit: String) {
+        let generation = self.services.load(Ordering::SeqCst);
+        self.run(move || Loaded::Service {
+            generation,
+            result: backend.fetch_service(&unit),
+            unit,
+        });
+    }
+
+    pub fn load_unit_file(&self, backend: B, unit: String) {
+        self.spawn(&self.panel, move |generation| Loaded::UnitFile {
+            generation,
+            result: backend.fetch_unit_file(&unit),
+        });
+    }
+
+    pub fn load_properties(&self, backend: B, unit: String) {
+        self.spawn(&self.panel, move |generation| Loaded::Properties {
+            generation,
+            result: backend.fetch_properties(&unit),
+        });
+    }
+
+    pub fn load_timers(&self, backend: B) {
+        self.spawn(&self.timers, move |generation| Loaded::Timers {
+            generation,
+            result: backend.fetch_timers(),
+        });
+    }
+
+    pub fn load_boots(&self, backend: B) {
+        self.spawn(&self.boots, move |generation| Loaded::Boots {
+            generation,
+            result: backend.fetch_boots(),
+        });
+    }
+
+    pub fn load_dependencies(
+        &self,
+        backend: B,
+        index: usize,
+        unit: String,
+        direction: DependencyDirection,
+    ) {
+        self.spawn(&self.deps, move |generation| Loaded::Dependencies {
+            generation,
+            index,
+            result: backend.fetch_dependencies(&unit, direction),
+            unit,
+            direction,
+        });
+    }
+
+    /// Supersedes any pending dependencies request, e.g. when the tree is rebuilt
+    pub fn discard_dependencies(&self) {
+        self.deps.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn run_action(&self, backend: B, unit: String, action: ServiceAction) {
+        self.run(move || Loaded::Action {
+            result: backend.run_action(&unit, action),
+            unit,
+            action,
+        });
+    }
+
+    pub fn daemon_reload(&self, backend: B, unit: String, path: PathBuf) {
+        self.run(move || Loaded::Reload {
+            result: backend.daemon_reload(),
+            unit,
+            path,
+        });
+    }
+
+    /// Reconnects to another service manager, superseding unit list and unit
+    /// requests made against the current one
+    pub fn switch_scope(&self, backend: B, scope: Scope) {
+        self.services.fetch_add(1, Ordering::SeqCst);
+        self.spawn(&self.scopes, move |generation| {
+            let mut backend = backend;
+            backend.set_scope(scope);
+            Loaded::Scope {
+                generation,
+                events: backend.subscribe(),
+                backend,
+            }
+        });
+    }
+
+    /// Whether any worker is still fetching or sending its result
+    pub fn busy(&self) -> bool {
+        self.running.load(Ordering::SeqCst) > 0
+    }
+
+    /// Starts a request of the kind counted by `kind`, superseding the previous one
+    fn spawn<F>(&self, kind: &Arc<AtomicU64>, fetch: F)
+    where
+        F: FnOnce(u64) -> Loaded<B> + Send + 'static,
+    {
+        let generation = kind.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = Arc::clone(kind);
+        let tx = self.tx.clone();
+        let running = Arc::clone(&self.running);
+        running.fetch_add(1, Ordering::SeqCst);
+        thread::spawn(move || {
+            if current.load(Ordering::SeqCst) == generation {
+                let _ = tx.send(fetch(generation));
+            }
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
+    /// Starts a request that nothing supersedes before it runs
+    fn run<F>(&self, fetch: F)
+    where
+        F: FnOnce() -> Loaded<B> + Send + 'static,
+    {
+        self.spawn(&Arc::new(AtomicU64::new(0)), move |_| fetch());
+    }
+
+    /// Results received since the last call that have not been superseded
+    pub fn drain(&self) -> Vec<Loaded<B>> {
+        let current = |kind: &AtomicU64, generation: u64| kind.load(Ordering::SeqCst) == generation;
+        self.rx
+            .try_iter()
+            .filter(|loaded| match *loaded {
+                Loaded::Services { generation, .. } => current(&self.services, generation),
+                Loaded::Logs { generation, .. } => current(&self.logs, generation),
+                Loaded::LogPage { generation, .. } => current(&self.pages, generation),
+                Loaded::Service { generation, .. } => current(&self.services, generation),
+                Loaded::UnitFile { generation, .. } | Loaded::Properties { generation, .. } => {
+                    current(&self.panel, generation)
+                }
+                Loaded::Timers { generation, .. } => current(&self.timers, generation),
+                Loaded::Boots { generation, .. } => current(&self.boots, generation),
+                Loaded::Dependencies { generation, .. } => current(&self.deps, generation),
+                Loaded::Action { .. } | Loaded::Reload { .. } => true,
+                Loaded::Scope { generation, .. } => current(&self.scopes, generation),
+            })
+            .collect()
+    }
+}
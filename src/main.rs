@@ -1,8 +1,27 @@
 mod app;
+mod backend;
+mod cli;
+mod clipboard;
+mod commands;
+mod config;
+mod dbus;
+mod deps;
+mod dropin;
+mod journal;
+mod keymap;
+mod loader;
+mod properties;
 mod service;
+mod snapshot;
+mod time;
 mod ui;
 
-use std::io::{self, stdout};
+use std::fs::{self, OpenOptions};
+use std::io::{self, stdout, Stdout, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+use std::time::Duration;
 
 use crossterm::{
     event::{
@@ -14,24 +33,148 @@ use crossterm::{
 };
 use ratatui::{prelude::*, Terminal};
 
-use app::App;
+use app::{App, PanelMode, StatusMessage, View};
+use backend::{JournalBackend, SnapshotBackend, SystemBackend, SystemdBackend};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use cli::{Cli, CliCommand};
+use config::Config;
+use journal::JournalSource;
+use keymap::KeyContext;
+use snapshot::Snapshot;
+
+/// How long to wait for input before redrawing, so followed logs keep streaming in
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// Faster redraws while a background load is running, to animate the spinner
+const LOADING_TICK_RATE: Duration = Duration::from_millis(100);
 
 fn main() -> io::Result<()> {
-    // Setup terminal with mouse capture
+    let cli = Cli::parse();
+    let (config, config_errors) = match cli.config {
+        Some(ref path) if !path.exists() => {
+            Cli::command()
+                .error(ErrorKind::Io, format!("config file {} not found", path.display()))
+                .exit()
+        }
+        Some(ref path) => Config::load(path),
+        None => config::default_path()
+            .map(|path| Config::load(&path))
+            .unwrap_or_default(),
+    };
+    if let Err(e) = cli.validate(&config) {
+        Cli::command().error(ErrorKind::InvalidValue, e).exit();
+    }
+    let scope = cli.scope();
+    let source = cli.journal_source();
+
+    let snapshot = cli.from_snapshot.as_deref().map(|path| {
+        Snapshot::load(path).unwrap_or_else(|e| {
+            eprintln!("systemdview: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    // A snapshot or offline journal replaces the live system as the source of units
+    let result = if let Some(snapshot) = snapshot {
+        dispatch(&cli, &config, config_errors, SnapshotBackend::new(scope, snapshot, source))
+    } else if source == JournalSource::Local {
+        dispatch(&cli, &config, config_errors, SystemBackend::connect(scope))
+    } else {
+        dispatch(&cli, &config, config_errors, JournalBackend::new(scope, source))
+    };
+
+    // Output piped into e.g. `head` may be closed early
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Runs what the command line asks for: a subcommand, a snapshot capture or the UI
+fn dispatch<B: SystemdBackend>(
+    cli: &Cli,
+    config: &Config,
+    config_errors: Vec<String>,
+    backend: B,
+) -> io::Result<()> {
+    if cli.command.is_some() || cli.save_snapshot.is_some() {
+        for error in &config_errors {
+            eprintln!("systemdview: config: {}", error);
+        }
+    }
+    if let Some(ref path) = cli.save_snapshot {
+        return commands::save_snapshot(&backend, path);
+    }
+    match cli.command {
+        Some(CliCommand::List(ref args)) => commands::list(&backend, args),
+        Some(CliCommand::Logs(ref args)) => commands::logs(&backend, args, config),
+        Some(CliCommand::Show(ref args)) => commands::show(&backend, args),
+        None => {
+            let mut app = App::new(backend);
+            config.apply(&mut app);
+            cli.startup.apply(&mut app);
+            if !config_errors.is_empty() {
+                app.message = Some(StatusMessage {
+                    text: format!("Config: {}", config_errors.join("; ")),
+                    is_error: true,
+                });
+            }
+            run_tui(app)
+        }
+    }
+}
+
+fn run_tui<B: SystemdBackend>(app: App<B>) -> io::Result<()> {
+    // Setup terminal, with mouse capture unless turned off
     enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    if app.mouse {
+        execute!(stdout(), EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let result = run(&mut terminal, app);
+
+    // Cleanup terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    result
+}
 
+/// Runs the event loop until the user quits
+fn run<B: SystemdBackend>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    mut app: App<B>,
+) -> io::Result<()> {
     loop {
+        app.logs_visible_lines =
+            ui::get_logs_visible_lines(&terminal.get_frame(), app.show_logs, app.list_width);
+        app.tick();
         terminal.draw(|frame| ui::render(frame, &mut app))?;
 
+        let tick_rate = if app.busy() {
+            LOADING_TICK_RATE
+        } else {
+            TICK_RATE
+        };
+        if !event::poll(tick_rate)? {
+            continue;
+        }
+
         match event::read()? {
             Event::Key(key) if key.kind == KeyEventKind::Press => {
-            // Help can be toggled from anywhere (except status picker)
-            if key.code == KeyCode::Char('?') && !app.show_status_picker {
+            // Any key dismisses the previous action result
+            app.message = None;
+
+            // Help can be toggled from anywhere (except modals)
+            if key.code == KeyCode::Char('?') && !app.modal_open() {
                 app.toggle_help();
                 continue;
             }
@@ -54,11 +197,183 @@ fn main() -> io::Result<()> {
                 continue;
             }
 
+            // Unit type picker modal
+            if app.show_type_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('t') => app.close_type_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.type_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.type_picker_previous(),
+                    KeyCode::Enter => app.type_picker_confirm(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Action picker modal
+            if app.show_action_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('a') => app.close_action_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.action_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.action_picker_previous(),
+                    KeyCode::Enter => app.action_picker_confirm(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Dependency tree modal
+            if app.deps.is_some() {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('d') => app.close_deps(),
+                    KeyCode::Down | KeyCode::Char('j') => app.deps_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.deps_previous(),
+                    KeyCode::Right | KeyCode::Char('l') => app.deps_expand(),
+                    KeyCode::Left | KeyCode::Char('h') => app.deps_collapse(),
+                    KeyCode::Char(' ') => app.deps_toggle(),
+                    KeyCode::Tab => app.deps_cycle_direction(),
+                    KeyCode::Enter => app.deps_select(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Log column picker modal
+            if app.show_column_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('C') => app.close_column_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.column_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.column_picker_previous(),
+                    KeyCode::Enter | KeyCode::Char(' ') => app.column_picker_toggle(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Log priority picker modal
+            if app.show_priority_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('p') => app.close_priority_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.priority_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.priority_picker_previous(),
+                    KeyCode::Enter => app.priority_picker_confirm(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Boot picker modal
+            if app.show_boot_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('b') => app.close_boot_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.boot_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.boot_picker_previous(),
+                    KeyCode::Enter => app.boot_picker_confirm(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Log time range picker modal
+            if app.show_time_picker {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('t') => app.close_time_picker(),
+                    KeyCode::Down | KeyCode::Char('j') => app.time_picker_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.time_picker_previous(),
+                    KeyCode::Enter => app.time_picker_confirm(),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Custom time range prompt
+            if let Some(ref mut input) = app.time_input {
+                match key.code {
+                    KeyCode::Esc => app.cancel_time_input(),
+                    KeyCode::Enter => app.confirm_time_input(),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Log entry detail modal
+            if app.entry_detail.is_some() {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                        app.close_entry_detail()
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => app.entry_detail_scroll_down(1),
+                    KeyCode::Up | KeyCode::Char('k') => app.entry_detail_scroll_up(1),
+                    KeyCode::PageDown => app.entry_detail_scroll_down(10),
+                    KeyCode::PageUp => app.entry_detail_scroll_up(10),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Action confirm dialog
+            if app.pending_action.is_some() {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => app.confirm_action(),
+                    KeyCode::Char('n') | KeyCode::Esc => app.cancel_action(),
+                    _ => {}
+                }
+                continue;
+            }
+
             // Calculate visible lines for scrolling
-            let visible_lines = ui::get_logs_visible_lines(&terminal.get_frame(), app.show_logs);
-            let visible_services = ui::get_services_visible_lines(&terminal.get_frame(), app.show_logs);
+            let frame = terminal.get_frame();
+            let visible_lines = ui::get_logs_visible_lines(&frame, app.show_logs, app.list_width);
+            let visible_services =
+                ui::get_services_visible_lines(&frame, app.show_logs, app.list_width);
 
-            if app.search_mode {
+            // Remapped keys arrive as the default key of their action, so the branches
+            // below match on defaults; Ctrl and Alt chords are never remapped
+            let context = if app.view == View::Timers {
+                KeyContext::Timers
+            } else if app.show_logs {
+                KeyContext::Panel
+            } else {
+                KeyContext::Units
+            };
+            let code = if key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                key.code
+            } else {
+                app.keymap.translate(context, key.code)
+            };
+            if app.view == View::Timers {
+                // Timers dashboard
+                match code {
+                    KeyCode::Char('q') => {
+                        app.should_quit = true;
+                    }
+                    KeyCode::Esc | KeyCode::Char('T') => {
+                        app.close_timers();
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.timers_next();
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.timers_previous();
+                    }
+                    KeyCode::Char('g') | KeyCode::Home => {
+                        app.timers_go_to_top();
+                    }
+                    KeyCode::Char('G') | KeyCode::End => {
+                        app.timers_go_to_bottom();
+                    }
+                    KeyCode::Char('r') => {
+                        app.load_timers();
+                    }
+                    KeyCode::Enter => {
+                        app.open_timer_target_logs();
+                    }
+                    _ => {}
+                }
+            } else if app.search_mode {
                 // Branch 1: Service search mode (only reachable when show_logs=false)
                 match key.code {
                     KeyCode::Esc | KeyCode::Enter => {
@@ -110,7 +425,7 @@ fn main() -> io::Result<()> {
                 }
             } else if app.show_logs {
                 // Branch 3: Log focus normal mode
-                match key.code {
+                match code {
                     KeyCode::Char('q') => {
                         app.should_quit = true;
                     }
@@ -122,18 +437,42 @@ fn main() -> io::Result<()> {
                         if !app.log_search_query.is_empty() {
                             app.clear_log_search();
                         } else {
-                            app.show_logs = false;
+                            app.toggle_logs();
                         }
                     }
                     KeyCode::Char('/') => {
                         app.log_search_mode = true;
                     }
+                    KeyCode::Char('f') if app.panel_mode == PanelMode::Logs => {
+                        app.toggle_follow();
+                    }
+                    KeyCode::Char('C') if app.panel_mode == PanelMode::Logs => {
+                        app.open_column_picker();
+                    }
+                    KeyCode::Char('p') if app.panel_mode == PanelMode::Logs => {
+                        app.open_priority_picker();
+                    }
+                    KeyCode::Char('t') if app.panel_mode == PanelMode::Logs => {
+                        app.open_time_picker();
+                    }
+                    KeyCode::Char('b') if app.panel_mode == PanelMode::Logs => {
+                        app.open_boot_picker();
+                    }
+                    KeyCode::Enter => {
+                        app.open_entry_detail();
+                    }
                     KeyCode::Char('n') => {
                         app.next_log_match(visible_lines);
                     }
                     KeyCode::Char('N') => {
                         app.prev_log_match(visible_lines);
                     }
+                    KeyCode::Char('y') => {
+                        app.copy_current_line();
+                    }
+                    KeyCode::Char('Y') => {
+                        app.copy_panel();
+                    }
                     KeyCode::Char('j') | KeyCode::Down => {
                         app.scroll_logs_down(1, visible_lines);
                     }
@@ -162,17 +501,15 @@ fn main() -> io::Result<()> {
                 }
             } else {
                 // Branch 4: Service normal mode
-                match key.code {
+                match code {
                     KeyCode::Char('q') => {
                         app.should_quit = true;
                     }
                     KeyCode::Char('l') => {
                         app.toggle_logs();
                     }
-                    KeyCode::Esc => {
-                        if !app.search_query.is_empty() {
-                            app.clear_search();
-                        }
+                    KeyCode::Esc if !app.search_query.is_empty() => {
+                        app.clear_search();
                     }
                     KeyCode::Char('/') => {
                         app.search_mode = true;
@@ -192,9 +529,42 @@ fn main() -> io::Result<()> {
                     KeyCode::Char('r') => {
                         app.load_services();
                     }
+                    KeyCode::Char('R') => {
+                        app.cycle_refresh_interval();
+                    }
+                    KeyCode::Char('x') => {
+                        app.acknowledge_change();
+                    }
+                    KeyCode::Char('X') => {
+                        app.acknowledge_all_changes();
+                    }
                     KeyCode::Char('s') => {
                         app.open_status_picker();
                     }
+                    KeyCode::Char('t') => {
+                        app.open_type_picker();
+                    }
+                    KeyCode::Char('a') => {
+                        app.open_action_picker();
+                    }
+                    KeyCode::Char('u') => {
+                        app.toggle_scope();
+                    }
+                    KeyCode::Char('T') => {
+                        app.open_timers();
+                    }
+                    KeyCode::Char('c') => {
+                        app.open_unit_file();
+                    }
+                    KeyCode::Char('e') => {
+                        app.request_edit();
+                    }
+                    KeyCode::Char('p') => {
+                        app.open_properties();
+                    }
+                    KeyCode::Char('d') => {
+                        app.open_deps();
+                    }
                     KeyCode::PageUp => {
                         app.page_up(visible_services);
                     }
@@ -213,31 +583,92 @@ fn main() -> io::Result<()> {
             _ => {}
         }
 
+        if let Some(text) = app.pending_copy.take() {
+            clipboard::copy(terminal.backend_mut(), &text)?;
+        }
+
+        if let Some(unit) = app.pending_edit.take() {
+            edit_override(terminal, &mut app, &unit)?;
+        }
+
         if app.should_quit {
             break;
         }
     }
+    Ok(())
+}
+
+/// Suspends the TUI, opens $EDITOR on a draft of the unit's override and applies the result
+fn edit_override<B: SystemdBackend>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App<B>,
+    unit: &str,
+) -> io::Result<()> {
+    let original = fs::read_to_string(dropin::override_path(app.backend.scope(), unit)).ok();
+
+    // Edit a scratch copy so a rejected edit never touches the real drop-in. It lives in
+    // a fresh private directory so no other user can plant a symlink at its path.
+    let scratch = tempfile::Builder::new().prefix("systemdview-").tempdir()?;
+    let draft = scratch.path().join(format!("{}.conf", unit.replace('/', "_")));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&draft)?
+        .write_all(
+            original
+                .clone()
+                .unwrap_or_else(|| dropin::template(unit))
+                .as_bytes(),
+        )?;
 
-    // Cleanup terminal
     disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+    let status = run_editor(&draft);
+
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    if app.mouse {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
+    terminal.clear()?;
+
+    let edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&draft).map_err(|e| e.to_string()),
+        Ok(status) => Err(format!("editor exited with {}", status)),
+        Err(e) => Err(format!("failed to start editor: {}", e)),
+    };
+    let _ = scratch.close();
+
+    app.finish_edit(unit, original.as_deref(), edited);
     Ok(())
 }
 
-fn handle_mouse_event(app: &mut App, mouse: MouseEvent, frame_size: Rect) {
-    // Don't handle mouse events when help or status picker is shown
-    if app.show_help || app.show_status_picker {
+fn run_editor(path: &Path) -> io::Result<ExitStatus> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    Command::new(program).args(parts).arg(path).status()
+}
+
+fn handle_mouse_event<B: SystemdBackend>(app: &mut App<B>, mouse: MouseEvent, frame_size: Rect) {
+    // Don't handle mouse events when help or a modal is shown
+    if app.show_help || app.modal_open() {
         return;
     }
 
-    let regions = ui::get_layout_regions(frame_size, app.show_logs);
+    let regions = ui::get_layout_regions(frame_size, app.show_logs, app.list_width);
 
-    if app.show_logs {
+    if app.view == View::Timers {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => app.timers_previous(),
+            MouseEventKind::ScrollDown => app.timers_next(),
+            _ => {}
+        }
+    } else if app.show_logs {
         // Log mode: all scroll events go to logs, clicks are ignored
         if let Some(logs) = regions.logs_panel {
             let visible = logs.height.saturating_sub(2) as usize;
@@ -254,24 +685,20 @@ fn handle_mouse_event(app: &mut App, mouse: MouseEvent, frame_size: Rect) {
     } else {
         // Service mode: existing behavior
         match mouse.kind {
-            MouseEventKind::Down(MouseButton::Left) => {
-                if mouse_in_rect(mouse, regions.services_list) {
-                    let y_in_list = mouse.row.saturating_sub(regions.services_list.y + 1);
-                    let clicked_index = app.list_state.offset() + y_in_list as usize;
-                    if clicked_index < app.filtered_indices.len() {
-                        app.list_state.select(Some(clicked_index));
-                    }
+            MouseEventKind::Down(MouseButton::Left)
+                if mouse_in_rect(mouse, regions.services_list) =>
+            {
+                let y_in_list = mouse.row.saturating_sub(regions.services_list.y + 1);
+                let clicked_index = app.list_state.offset() + y_in_list as usize;
+                if clicked_index < app.filtered_indices.len() {
+                    app.list_state.select(Some(clicked_index));
                 }
             }
-            MouseEventKind::ScrollUp => {
-                if mouse_in_rect(mouse, regions.services_list) {
-                    app.previous();
-                }
+            MouseEventKind::ScrollUp if mouse_in_rect(mouse, regions.services_list) => {
+                app.previous();
             }
-            MouseEventKind::ScrollDown => {
-                if mouse_in_rect(mouse, regions.services_list) {
-                    app.next();
-                }
+            MouseEventKind::ScrollDown if mouse_in_rect(mouse, regions.services_list) => {
+                app.next();
             }
             _ => {}
         }
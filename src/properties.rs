@@ -0,0 +1,369 @@
+/// Keys whose values are whitespace-separated unit lists
+const DEPENDENCY_KEYS: [&str; 25] = [
+    "Requires",
+    "Requisite",
+    "Wants",
+    "BindsTo",
+    "PartOf",
+    "Upholds",
+    "RequiredBy",
+    "RequisiteOf",
+    "WantedBy",
+    "BoundBy",
+    "UpheldBy",
+    "ConsistsOf",
+    "Conflicts",
+    "ConflictedBy",
+    "Before",
+    "After",
+    "OnSuccess",
+    "OnSuccessOf",
+    "OnFailure",
+    "OnFailureOf",
+    "Triggers",
+    "TriggeredBy",
+    "PropagatesReloadTo",
+    "ReloadPropagatedFrom",
+    "JoinsNamespaceOf",
+];
+
+const GENERAL_KEYS: [&str; 13] = [
+    "Id",
+    "Names",
+    "Description",
+    "LoadState",
+    "ActiveState",
+    "SubState",
+    "FreezerState",
+    "UnitFileState",
+    "UnitFilePreset",
+    "Result",
+    "StatusText",
+    "StatusErrno",
+    "InvocationID",
+];
+
+/// systemd prints u64::MAX for counters that are not tracked
+const NOT_SET: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyGroup {
+    General,
+    Process,
+    Resources,
+    Timestamps,
+    Paths,
+    Dependencies,
+    Other,
+}
+
+impl PropertyGroup {
+    pub const ALL: [PropertyGroup; 7] = [
+        PropertyGroup::General,
+        PropertyGroup::Process,
+        PropertyGroup::Resources,
+        PropertyGroup::Timestamps,
+        PropertyGroup::Paths,
+        PropertyGroup::Dependencies,
+        PropertyGroup::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PropertyGroup::General => "General",
+            PropertyGroup::Process => "Process",
+            PropertyGroup::Resources => "Resources",
+            PropertyGroup::Timestamps => "Timestamps",
+            PropertyGroup::Paths => "Paths",
+            PropertyGroup::Dependencies => "Dependencies",
+            PropertyGroup::Other => "Other",
+        }
+    }
+
+    fn of(key: &str) -> Self {
+        if GENERAL_KEYS.contains(&key) {
+            PropertyGroup::General
+        } else if DEPENDENCY_KEYS.contains(&key) {
+            PropertyGroup::Dependencies
+        } else if key.ends_with("Timestamp") || key.ends_with("TimestampMonotonic") {
+            PropertyGroup::Timestamps
+        } else if key.ends_with("Path")
+            || key.ends_with("Paths")
+            || key.ends_with("Directory")
+            || key.ends_with("Directories")
+        {
+            PropertyGroup::Paths
+        } else if key.ends_with("PID")
+            || key.starts_with("ExecMain")
+            || key.starts_with("Exec")
+            || key.starts_with("Restart")
+            || key.starts_with("Kill")
+            || key == "NRestarts"
+            || key == "Type"
+            || key == "User"
+            || key == "Group"
+        {
+            PropertyGroup::Process
+        } else if key.starts_with("Memory")
+            || key.starts_with("CPU")
+            || key.starts_with("Tasks")
+            || key.starts_with("IO")
+            || key.starts_with("IP")
+            || key.starts_with("Limit")
+        {
+            PropertyGroup::Resources
+        } else {
+            PropertyGroup::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Empty,
+    Bool(bool),
+    Integer(u64),
+    /// A byte count, e.g. MemoryCurrent
+    Bytes(u64),
+    /// A nanosecond duration, e.g. CPUUsageNSec
+    Nanoseconds(u64),
+    /// A timestamp already formatted by systemctl
+    Timestamp(String),
+    List(Vec<String>),
+    Text(String),
+}
+
+impl PropertyValue {
+    fn parse(key: &str, raw: &str) -> Self {
+        if raw.is_empty() {
+            return PropertyValue::Empty;
+        }
+        if DEPENDENCY_KEYS.contains(&key) {
+            return PropertyValue::List(raw.split_whitespace().map(String::from).collect());
+        }
+        if key.ends_with("Timestamp") {
+            return PropertyValue::Timestamp(raw.to_string());
+        }
+        match raw {
+            "yes" => return PropertyValue::Bool(true),
+            "no" => return PropertyValue::Bool(false),
+            _ => {}
+        }
+        match raw.parse::<u64>() {
+            Ok(n) if key.ends_with("NSec") => PropertyValue::Nanoseconds(n),
+            Ok(n) if key.starts_with("Memory") || key.ends_with("Bytes") => {
+                PropertyValue::Bytes(n)
+            }
+            Ok(n) => PropertyValue::Integer(n),
+            Err(_) => PropertyValue::Text(raw.to_string()),
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            PropertyValue::Integer(n) | PropertyValue::Bytes(n) | PropertyValue::Nanoseconds(n)
+                if *n != NOT_SET =>
+            {
+                Some(*n)
+            }
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            PropertyValue::Text(s) | PropertyValue::Timestamp(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The value as `systemctl show` printed it
+    pub fn raw(&self) -> String {
+        match self {
+            PropertyValue::Empty => String::new(),
+            PropertyValue::Bool(b) => if *b { "yes" } else { "no" }.to_string(),
+            PropertyValue::Integer(n) | PropertyValue::Bytes(n) | PropertyValue::Nanoseconds(n) => {
+                n.to_string()
+            }
+            PropertyValue::Timestamp(s) | PropertyValue::Text(s) => s.clone(),
+            PropertyValue::List(items) => items.join(" "),
+        }
+    }
+
+    /// Human readable form, e.g. "12.5M" for bytes or "1.204s" for nanoseconds
+    pub fn display(&self) -> String {
+        match self {
+            PropertyValue::Empty => String::new(),
+            PropertyValue::Bool(b) => if *b { "yes" } else { "no" }.to_string(),
+            PropertyValue::Integer(n) | PropertyValue::Bytes(n) | PropertyValue::Nanoseconds(n)
+                if *n == NOT_SET =>
+            {
+                "[not set]".to_string()
+            }
+            PropertyValue::Integer(n) => n.to_string(),
+            PropertyValue::Bytes(n) => format_bytes(*n),
+            PropertyValue::Nanoseconds(n) => format_nanoseconds(*n),
+            PropertyValue::Timestamp(s) | PropertyValue::Text(s) => s.clone(),
+            PropertyValue::List(items) => items.join(" "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub key: String,
+    pub value: PropertyValue,
+    pub group: PropertyGroup,
+}
+
+/// Parsed `systemctl show` output for one unit
+#[derive(Debug, Clone, Default)]
+pub struct UnitProperties {
+    pub properties: Vec<Property>,
+}
+
+impl UnitProperties {
+    /// Parses `Key=Value` lines; values may themselves contain '='
+    pub fn parse(text: &str) -> Self {
+        let properties = text
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, raw)| Property {
+                key: key.to_string(),
+                value: PropertyValue::parse(key, raw),
+                group: PropertyGroup::of(key),
+            })
+            .collect();
+        Self { properties }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PropertyValue> {
+        self.properties
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| &p.value)
+    }
+
+    /// Text of a property, empty if missing or not textual
+    pub fn text(&self, key: &str) -> &str {
+        self.get(key).and_then(PropertyValue::as_str).unwrap_or("")
+    }
+
+    /// Items of a unit-list property such as Requires, empty if missing
+    pub fn list(&self, key: &str) -> &[String] {
+        match self.get(key) {
+            Some(PropertyValue::List(items)) => items,
+            _ => &[],
+        }
+    }
+
+    pub fn main_pid(&self) -> Option<u64> {
+        self.get("MainPID")
+            .and_then(PropertyValue::as_u64)
+            .filter(|&pid| pid > 0)
+    }
+
+    pub fn n_restarts(&self) -> Option<u64> {
+        self.get("NRestarts").and_then(PropertyValue::as_u64)
+    }
+
+    pub fn result(&self) -> Option<&str> {
+        self.get("Result").and_then(PropertyValue::as_str)
+    }
+
+    pub fn memory_current(&self) -> Option<u64> {
+        self.get("MemoryCurrent").and_then(PropertyValue::as_u64)
+    }
+
+    pub fn cpu_usage_nsec(&self) -> Option<u64> {
+        self.get("CPUUsageNSec").and_then(PropertyValue::as_u64)
+    }
+
+    pub fn tasks_current(&self) -> Option<u64> {
+        self.get("TasksCurrent").and_then(PropertyValue::as_u64)
+    }
+
+    pub fn exec_main_start_timestamp(&self) -> Option<&str> {
+        self.get("ExecMainStartTimestamp")
+            .and_then(PropertyValue::as_str)
+    }
+
+    pub fn fragment_path(&self) -> Option<&str> {
+        self.get("FragmentPath").and_then(PropertyValue::as_str)
+    }
+
+    /// One-line summary of the most useful properties
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(pid) = self.main_pid() {
+            parts.push(format!("PID {}", pid));
+        }
+        if let Some(result) = self.result() {
+            parts.push(format!("result {}", result));
+        }
+        if let Some(restarts) = self.n_restarts() {
+            parts.push(format!("{} restarts", restarts));
+        }
+        if let Some(memory) = self.memory_current() {
+            parts.push(format!("mem {}", format_bytes(memory)));
+        }
+        if let Some(cpu) = self.cpu_usage_nsec() {
+            parts.push(format!("cpu {}", format_nanoseconds(cpu)));
+        }
+        if let Some(tasks) = self.tasks_current() {
+            parts.push(format!("{} tasks", tasks));
+        }
+        if let Some(started) = self.exec_main_start_timestamp() {
+            parts.push(format!("started {}", started));
+        }
+        if let Some(path) = self.fragment_path() {
+            parts.push(path.to_string());
+        }
+        parts.join(" | ")
+    }
+
+    /// Display lines for the side panel: a summary, then `Key=Value` lines per group
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.summary()];
+        for group in PropertyGroup::ALL {
+            let mut members = self
+                .properties
+                .iter()
+                .filter(|p| p.group == group)
+                .peekable();
+            if members.peek().is_none() {
+                continue;
+            }
+            lines.push(String::new());
+            lines.push(format!("{}{}", GROUP_MARKER, group.label()));
+            lines.extend(members.map(|p| format!("{}={}", p.key, p.value.display())));
+        }
+        lines
+    }
+}
+
+/// Prefix of group heading lines produced by `UnitProperties::to_lines`
+pub const GROUP_MARKER: &str = "▶ ";
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+fn format_nanoseconds(nsec: u64) -> String {
+    if nsec >= 1_000_000_000 {
+        format!("{:.3}s", nsec as f64 / 1e9)
+    } else {
+        format!("{}ms", nsec / 1_000_000)
+    }
+}
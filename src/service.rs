@@ -1,8 +1,14 @@
 use ratatui::style::Color;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
-#[derive(Debug, Clone, Deserialize)]
+use crate::app::UNIT_TYPE_OPTIONS;
+use crate::deps::DependencyDirection;
+use crate::properties::UnitProperties;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SystemdService {
     pub unit: String,
     #[allow(dead_code)]
@@ -11,6 +17,15 @@ pub struct SystemdService {
     pub active: String,
     pub sub: String,
     pub description: String,
+    /// Enablement state from `systemctl list-unit-files`, merged in after listing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_file_state: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnitFile {
+    unit_file: String,
+    state: String,
 }
 
 impl SystemdService {
@@ -18,32 +33,377 @@ impl SystemdService {
         &self.sub
     }
 
+    /// Unit type taken from the name suffix, e.g. "timer" for "fstrim.timer"
+    pub fn unit_type(&self) -> &str {
+        self.unit.rsplit_once('.').map_or("", |(_, suffix)| suffix)
+    }
+
     pub fn status_color(&self) -> Color {
-        match self.sub.as_str() {
-            "running" => Color::Green,
-            "exited" => Color::Yellow,
-            "dead" | "stopped" => Color::DarkGray,
-            "failed" => Color::Red,
+        match (self.unit_type(), self.sub.as_str()) {
+            (_, "running") => Color::Green,
+            (_, "exited") => Color::Yellow,
+            (_, "dead" | "stopped") => Color::DarkGray,
+            (_, "failed") => Color::Red,
+            ("timer" | "path" | "automount", "waiting") => Color::Cyan,
+            ("timer", "elapsed") => Color::Yellow,
+            ("socket", "listening") => Color::Green,
+            ("mount" | "automount" | "swap", "mounted" | "active") => Color::Green,
+            ("target" | "slice" | "scope", "active") => Color::Green,
+            ("scope", "abandoned") => Color::Yellow,
+            ("device", "plugged") => Color::Green,
+            ("device", "tentative") => Color::Yellow,
+            (_, "activating" | "deactivating" | "reloading") => Color::Magenta,
+            _ => Color::White,
+        }
+    }
+
+    pub fn enablement_display(&self) -> &str {
+        self.unit_file_state.as_deref().unwrap_or("-")
+    }
+
+    pub fn enablement_color(&self) -> Color {
+        match self.enablement_display() {
+            "enabled" | "enabled-runtime" => Color::Green,
+            "disabled" => Color::DarkGray,
+            "static" | "indirect" => Color::Cyan,
+            "masked" | "masked-runtime" | "bad" => Color::Red,
+            "generated" | "transient" => Color::Magenta,
+            "alias" | "linked" | "linked-runtime" => Color::Blue,
             _ => Color::White,
         }
     }
+
+    /// Builds a row from `systemctl show` output (Id, LoadState, ActiveState, SubState, ...)
+    fn from_properties(properties: &UnitProperties) -> Self {
+        let unit_file_state = properties.text("UnitFileState");
+        Self {
+            unit: properties.text("Id").to_string(),
+            load: properties.text("LoadState").to_string(),
+            active: properties.text("ActiveState").to_string(),
+            sub: properties.text("SubState").to_string(),
+            description: properties.text("Description").to_string(),
+            unit_file_state: (!unit_file_state.is_empty()).then(|| unit_file_state.to_string()),
+        }
+    }
+
+    /// A unit that has a unit file but is not currently loaded by systemd
+    pub fn unloaded(unit: String, unit_file_state: String) -> Self {
+        Self {
+            unit,
+            load: "not-loaded".to_string(),
+            active: "inactive".to_string(),
+            sub: "dead".to_string(),
+            description: String::new(),
+            unit_file_state: Some(unit_file_state),
+        }
+    }
+
+    /// A unit only known from journal entries, whose live state is unknown
+    pub fn from_journal(unit: String) -> Self {
+        Self {
+            unit,
+            load: "journal".to_string(),
+            active: "unknown".to_string(),
+            sub: "unknown".to_string(),
+            description: String::new(),
+            unit_file_state: None,
+        }
+    }
+}
+
+/// Search and status filter of the unit list, shared by the UI and `systemdview list`
+pub struct ServiceFilter {
+    /// Lowercased text matched against the unit name and description
+    search: String,
+    /// Exact sub-state, e.g. "failed"
+    status: Option<String>,
 }
 
-pub fn fetch_logs(unit_name: &str, lines: usize) -> Result<Vec<String>, String> {
-    let output = Command::new("journalctl")
-        .args(["-u", unit_name, "-n", &lines.to_string(), "--no-pager"])
+impl ServiceFilter {
+    pub fn new(search: &str, status: Option<&str>) -> Self {
+        Self {
+            search: search.to_lowercase(),
+            status: status.map(str::to_string),
+        }
+    }
+
+    pub fn matches(&self, service: &SystemdService) -> bool {
+        let matches_search = self.search.is_empty()
+            || service.unit.to_lowercase().contains(&self.search)
+            || service.description.to_lowercase().contains(&self.search);
+        let matches_status = self.status.as_ref().is_none_or(|status| *status == service.sub);
+        matches_search && matches_status
+    }
+}
+
+/// A row of `systemctl list-timers`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemdTimer {
+    pub unit: String,
+    pub activates: String,
+    /// Next elapse, in microseconds since the epoch
+    #[serde(default)]
+    next: Option<u64>,
+    /// Last trigger, in microseconds since the epoch
+    #[serde(default)]
+    last: Option<u64>,
+}
+
+impl SystemdTimer {
+    pub fn next_elapse(&self) -> Option<u64> {
+        self.next.filter(|&t| t > 0)
+    }
+
+    pub fn last_trigger(&self) -> Option<u64> {
+        self.last.filter(|&t| t > 0)
+    }
+
+    /// Time left until the next elapse
+    pub fn left(&self, now: u64) -> Option<u64> {
+        self.next_elapse().map(|next| next.saturating_sub(now))
+    }
+
+    /// Time passed since the last trigger
+    pub fn passed(&self, now: u64) -> Option<u64> {
+        self.last_trigger().map(|last| now.saturating_sub(last))
+    }
+}
+
+/// Which service manager to talk to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    #[default]
+    System,
+    User,
+}
+
+impl Scope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Scope::System => "system",
+            Scope::User => "user",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Scope::System => Scope::User,
+            Scope::User => Scope::System,
+        }
+    }
+
+    fn systemctl(&self) -> Command {
+        let mut cmd = Command::new("systemctl");
+        cmd.arg(match self {
+            Scope::System => "--system",
+            Scope::User => "--user",
+        });
+        cmd
+    }
+
+    /// journalctl option that filters by unit within this manager
+    pub fn journal_unit_flag(&self) -> &'static str {
+        match self {
+            Scope::System => "-u",
+            Scope::User => "--user-unit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+    TryRestart,
+    Enable,
+    Disable,
+    Mask,
+    Unmask,
+    EnableNow,
+}
+
+impl ServiceAction {
+    pub const ALL: [ServiceAction; 10] = [
+        ServiceAction::Start,
+        ServiceAction::Stop,
+        ServiceAction::Restart,
+        ServiceAction::Reload,
+        ServiceAction::TryRestart,
+        ServiceAction::Enable,
+        ServiceAction::Disable,
+        ServiceAction::Mask,
+        ServiceAction::Unmask,
+        ServiceAction::EnableNow,
+    ];
+
+    /// The systemctl arguments for this action, before the unit name
+    pub fn args(&self) -> &'static [&'static str] {
+        match self {
+            ServiceAction::Start => &["start"],
+            ServiceAction::Stop => &["stop"],
+            ServiceAction::Restart => &["restart"],
+            ServiceAction::Reload => &["reload"],
+            ServiceAction::TryRestart => &["try-restart"],
+            ServiceAction::Enable => &["enable"],
+            ServiceAction::Disable => &["disable"],
+            ServiceAction::Mask => &["mask"],
+            ServiceAction::Unmask => &["unmask"],
+            ServiceAction::EnableNow => &["enable", "--now"],
+        }
+    }
+
+    /// The systemctl verb for this action, as shown to the user
+    pub fn verb(&self) -> String {
+        self.args().join(" ")
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "Start",
+            ServiceAction::Stop => "Stop",
+            ServiceAction::Restart => "Restart",
+            ServiceAction::Reload => "Reload",
+            ServiceAction::TryRestart => "Try-restart",
+            ServiceAction::Enable => "Enable",
+            ServiceAction::Disable => "Disable",
+            ServiceAction::Mask => "Mask",
+            ServiceAction::Unmask => "Unmask",
+            ServiceAction::EnableNow => "Enable --now",
+        }
+    }
+}
+
+/// Runs `systemctl <verb> <unit>` and returns its output on success
+pub fn run_action(
+    scope: Scope,
+    unit_name: &str,
+    action: ServiceAction,
+) -> Result<String, String> {
+    let output = scope
+        .systemctl()
+        .arg("--no-ask-password")
+        .args(action.args())
+        .arg(unit_name)
         .output()
-        .map_err(|e| format!("Failed to execute journalctl: {}", e))?;
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
 
-    Ok(String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|s| s.to_string())
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+    if !output.status.success() {
+        return Err(if stderr.is_empty() {
+            format!("systemctl {} {} failed ({})", action.verb(), unit_name, output.status)
+        } else {
+            stderr
+        });
+    }
+
+    Ok(if stdout.is_empty() { stderr } else { stdout })
+}
+
+/// Runs `systemctl daemon-reload` so edited unit files take effect
+pub fn daemon_reload(scope: Scope) -> Result<(), String> {
+    let output = scope
+        .systemctl()
+        .args(["--no-ask-password", "daemon-reload"])
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "daemon-reload failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Lists loaded units of the given type (e.g. "timer"), or of every type when `None`
+pub fn fetch_services(
+    scope: Scope,
+    unit_type: Option<&str>,
+) -> Result<Vec<SystemdService>, String> {
+    let type_arg = unit_type.map(|t| format!("--type={}", t));
+    let output = scope
+        .systemctl()
+        .args(["list-units", "--all", "--no-pager", "--output=json"])
+        .args(&type_arg)
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let mut services: Vec<SystemdService> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    // Enablement is best-effort; the list is still useful without it
+    let type_args: Vec<&str> = type_arg.as_deref().into_iter().collect();
+    let states = fetch_unit_file_states(scope, &type_args).unwrap_or_default();
+    merge_unit_file_states(&mut services, states);
+
+    Ok(services)
+}
+
+/// Fills in enablement states and appends unit files that are installed but not loaded
+/// (e.g. disabled services)
+pub fn merge_unit_file_states(
+    services: &mut Vec<SystemdService>,
+    mut states: HashMap<String, String>,
+) {
+    for service in services.iter_mut() {
+        service.unit_file_state = states.remove(&service.unit);
+    }
+
+    let mut unloaded: Vec<SystemdService> = states
+        .into_iter()
+        .filter(|(unit, _)| !unit.contains("@."))
+        .map(|(unit, state)| SystemdService::unloaded(unit, state))
+        .collect();
+    unloaded.sort_by(|a, b| a.unit.cmp(&b.unit));
+    services.extend(unloaded);
+}
+
+/// Maps unit file names to their enablement state
+fn fetch_unit_file_states(
+    scope: Scope,
+    extra_args: &[&str],
+) -> Result<HashMap<String, String>, String> {
+    let output = scope
+        .systemctl()
+        .args(["list-unit-files", "--no-pager", "--output=json"])
+        .args(extra_args)
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let unit_files: Vec<UnitFile> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    Ok(unit_files
+        .into_iter()
+        .map(|f| (f.unit_file, f.state))
         .collect())
 }
 
-pub fn fetch_services() -> Result<Vec<SystemdService>, String> {
-    let output = Command::new("systemctl")
-        .args(["list-units", "--type=service", "--all", "--no-pager", "--output=json"])
+/// Fetches the current state of a single unit, `None` if systemd no longer knows it
+pub fn fetch_service(scope: Scope, unit_name: &str) -> Result<Option<SystemdService>, String> {
+    let output = scope
+        .systemctl()
+        .args(["list-units", "--all", "--no-pager", "--output=json", unit_name])
         .output()
         .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
 
@@ -57,5 +417,209 @@ pub fn fetch_services() -> Result<Vec<SystemdService>, String> {
     let services: Vec<SystemdService> = serde_json::from_slice(&output.stdout)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
 
-    Ok(services)
+    let state = fetch_unit_file_states(scope, &[unit_name])
+        .unwrap_or_default()
+        .remove(unit_name);
+
+    Ok(match services.into_iter().find(|s| s.unit == unit_name) {
+        Some(mut service) => {
+            service.unit_file_state = state;
+            Some(service)
+        }
+        None => state.map(|state| SystemdService::unloaded(unit_name.to_string(), state)),
+    })
+}
+
+pub fn fetch_timers(scope: Scope) -> Result<Vec<SystemdTimer>, String> {
+    let output = scope
+        .systemctl()
+        .args(["list-timers", "--all", "--no-pager", "--output=json"])
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "systemctl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+/// Prefix that replaces the `# /path` header systemctl cat prints for the main unit file
+pub const UNIT_FILE_MAIN_MARKER: &str = "▶ main: ";
+/// Prefix that replaces the `# /path` header systemctl cat prints for each drop-in
+pub const UNIT_FILE_DROP_IN_MARKER: &str = "▶ drop-in: ";
+
+/// Fetches `systemctl cat` output, with file headers rewritten into main/drop-in markers
+pub fn fetch_unit_file(scope: Scope, unit_name: &str) -> Result<Vec<String>, String> {
+    let output = scope
+        .systemctl()
+        .args(["cat", "--no-pager", unit_name])
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(mark_unit_file_headers(
+        &String::from_utf8_lossy(&output.stdout),
+        |path| Path::new(path).is_file(),
+    ))
+}
+
+/// Replaces the `# <path>` header `systemctl cat` puts above each file with a marker.
+///
+/// Headers are the first line and lines right after a blank line, and only when
+/// they name an existing unit file or drop-in; anything else is a comment in a file.
+fn mark_unit_file_headers(text: &str, is_file: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut seen_main = false;
+    let mut previous_blank = true;
+    text.lines()
+        .map(|line| {
+            let header = line
+                .strip_prefix("# ")
+                .filter(|path| previous_blank && is_unit_file_path(path) && is_file(path));
+            previous_blank = line.trim().is_empty();
+            let Some(path) = header else {
+                return line.to_string();
+            };
+            if seen_main {
+                format!("{}{}", UNIT_FILE_DROP_IN_MARKER, path)
+            } else {
+                seen_main = true;
+                format!("{}{}", UNIT_FILE_MAIN_MARKER, path)
+            }
+        })
+        .collect()
+}
+
+fn is_unit_file_path(path: &str) -> bool {
+    let path = Path::new(path);
+    path.is_absolute()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ext == "conf" || UNIT_TYPE_OPTIONS[1..].contains(&ext))
+}
+
+/// Fetches every `systemctl show` property of a unit, including empty ones
+pub fn fetch_properties(scope: Scope, unit_name: &str) -> Result<UnitProperties, String> {
+    let output = scope
+        .systemctl()
+        .args(["show", "--all", "--no-pager", unit_name])
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(UnitProperties::parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Fetches a few properties of several units with a single `systemctl show` call
+fn fetch_properties_of(
+    scope: Scope,
+    units: &[String],
+    keys: &[&str],
+) -> Result<Vec<UnitProperties>, String> {
+    let output = scope
+        .systemctl()
+        .args(["show", "--no-pager", "-p", &keys.join(",")])
+        .args(units)
+        .output()
+        .map_err(|e| format!("Failed to execute systemctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    // One block per unit, separated by blank lines
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(UnitProperties::parse)
+        .collect())
+}
+
+/// Direct dependencies of a unit in the given direction, with their current state
+pub fn fetch_dependencies(
+    scope: Scope,
+    unit_name: &str,
+    direction: DependencyDirection,
+) -> Result<Vec<SystemdService>, String> {
+    let edges = fetch_properties_of(scope, &[unit_name.to_string()], direction.properties())?;
+
+    let mut units: Vec<String> = edges
+        .iter()
+        .flat_map(|props| direction.properties().iter().map(|key| props.list(key)))
+        .flatten()
+        .cloned()
+        .collect();
+    units.sort();
+    units.dedup();
+
+    if units.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let states = fetch_properties_of(
+        scope,
+        &units,
+        &["Id", "LoadState", "ActiveState", "SubState", "Description", "UnitFileState"],
+    )?;
+    Ok(states.iter().map(SystemdService::from_properties).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_file_headers_must_name_existing_files() {
+        let text = "# /usr/lib/systemd/system/cron.service\n\
+                    [Unit]\n\
+                    Description=Regular background program processing daemon\n\
+                    \n\
+                    # /etc/cron.d is read on start\n\
+                    # /usr/lib/systemd/system/old.service was merged into this unit\n\
+                    [Service]\n\
+                    ExecStart=/usr/sbin/cron -f\n\
+                    \n\
+                    # /etc/systemd/system/cron.service.d/override.conf\n\
+                    [Service]\n\
+                    Nice=5\n";
+        let files = [
+            "/usr/lib/systemd/system/cron.service",
+            "/etc/systemd/system/cron.service.d/override.conf",
+        ];
+        let lines = mark_unit_file_headers(text, |path| files.contains(&path));
+
+        assert_eq!(lines[0], format!("{}{}", UNIT_FILE_MAIN_MARKER, files[0]));
+        assert_eq!(lines[4], "# /etc/cron.d is read on start");
+        assert_eq!(
+            lines[5],
+            "# /usr/lib/systemd/system/old.service was merged into this unit"
+        );
+        assert_eq!(
+            lines[9],
+            format!("{}{}", UNIT_FILE_DROP_IN_MARKER, files[1])
+        );
+    }
+
+    #[test]
+    fn unit_file_comment_after_a_blank_line_is_not_a_header() {
+        let text = "# /etc/systemd/system/backup.service\n\
+                    [Service]\n\
+                    \n\
+                    # /etc/backup/targets lists what gets copied\n\
+                    ExecStart=/usr/local/bin/backup\n";
+        let lines = mark_unit_file_headers(text, |_| true);
+
+        assert!(lines[0].starts_with(UNIT_FILE_MAIN_MARKER));
+        assert_eq!(lines[3], "# /etc/backup/targets lists what gets copied");
+    }
 }
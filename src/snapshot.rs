@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::SystemdBackend;
+use crate::properties::UnitProperties;
+use crate::service::{Scope, SystemdService};
+
+/// Unit state captured on one machine, to be browsed read-only on another
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Snapshot {
+    /// Service manager the units were read from; unknown for bare `list-units` output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Scope>,
+    /// Rows in `systemctl list-units --output=json` form
+    pub units: Vec<SystemdService>,
+    /// `systemctl show` properties of the units they were captured for
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Snapshot files are either a full snapshot or bare `systemctl list-units` output
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnapshotFile {
+    Snapshot(Snapshot),
+    Units(Vec<SystemdService>),
+}
+
+impl Snapshot {
+    /// Reads every unit of `backend` with its properties
+    pub fn capture<B: SystemdBackend>(backend: &B) -> Result<Self, String> {
+        let units = backend.fetch_services(None)?;
+        // Units whose properties cannot be read are kept without them
+        let properties = units
+            .iter()
+            .filter_map(|service| {
+                let properties = backend.fetch_properties(&service.unit).ok()?;
+                let values = properties
+                    .properties
+                    .into_iter()
+                    .map(|p| (p.key, p.value.raw()))
+                    .collect();
+                Some((service.unit.clone(), values))
+            })
+            .collect();
+        Ok(Self {
+            scope: Some(backend.scope()),
+            units,
+            properties,
+        })
+    }
+
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let file: SnapshotFile =
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse snapshot: {}", e))?;
+        Ok(match file {
+            SnapshotFile::Snapshot(snapshot) => snapshot,
+            SnapshotFile::Units(units) => Self {
+                units,
+                ..Self::default()
+            },
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Captured properties of a unit, `None` if it had none
+    pub fn unit_properties(&self, unit_name: &str) -> Option<UnitProperties> {
+        let values = self.properties.get(unit_name)?;
+        let text = values
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(UnitProperties::parse(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{loaded_app, sample, settle};
+    use crate::backend::SnapshotBackend;
+    use crate::journal::JournalSource;
+
+    #[test]
+    fn parses_bare_list_units_output() {
+        let snapshot = Snapshot::parse(
+            r#"[{"unit":"ssh.service","load":"loaded","active":"active","sub":"running",
+                 "description":"OpenBSD Secure Shell server"}]"#,
+        )
+        .unwrap();
+        assert_eq!(snapshot.units.len(), 1);
+        assert_eq!(snapshot.units[0].sub, "running");
+        assert!(snapshot.properties.is_empty());
+    }
+
+    #[test]
+    fn captured_snapshot_round_trips() {
+        let snapshot = Snapshot::capture(&sample()).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let loaded = Snapshot::parse(&json).unwrap();
+
+        let units: Vec<&str> = loaded.units.iter().map(|s| s.unit.as_str()).collect();
+        assert_eq!(
+            units,
+            ["cron.service", "nginx.service", "ssh.service", "logrotate.timer", "tmp.mount"]
+        );
+        let properties = loaded.unit_properties("nginx.service").unwrap();
+        assert_eq!(properties.text("ActiveState"), "failed");
+    }
+
+    #[test]
+    fn snapshot_app_is_read_only() {
+        let snapshot = Snapshot::capture(&sample()).unwrap();
+        let backend = SnapshotBackend::new(Scope::System, snapshot, JournalSource::Local);
+        let mut app = loaded_app(backend);
+        assert_eq!(app.services.len(), 3);
+
+        app.open_action_picker();
+        assert!(!app.show_action_picker);
+        let message = app.message.take().unwrap();
+        assert!(message.is_error);
+        assert_eq!(message.text, "Actions unavailable in a read-only snapshot");
+
+        app.open_properties();
+        settle(&mut app);
+        assert!(app.properties_lines.iter().any(|line| line.contains("running")));
+    }
+
+    #[test]
+    fn snapshot_keeps_the_scope_it_was_captured_in() {
+        let mut live = sample();
+        live.set_scope(Scope::User);
+        let snapshot = Snapshot::capture(&live).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains(r#""scope":"user""#), "{}", json);
+
+        // The recorded scope wins over --user being left out
+        let snapshot = Snapshot::parse(&json).unwrap();
+        let backend = SnapshotBackend::new(Scope::System, snapshot, JournalSource::Local);
+        let mut app = loaded_app(backend);
+        assert_eq!(app.backend.scope(), Scope::User);
+
+        app.toggle_scope();
+        settle(&mut app);
+        assert_eq!(app.backend.scope(), Scope::User);
+        assert_eq!(app.services.len(), 3);
+        let message = app.message.take().unwrap();
+        assert!(message.is_error);
+        assert_eq!(message.text, "The snapshot only holds user units");
+    }
+}
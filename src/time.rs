@@ -0,0 +1,44 @@
+use chrono::{Local, TimeZone};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USEC_PER_SEC: u64 = 1_000_000;
+
+/// Current wall-clock time in microseconds since the epoch, as systemd reports it
+pub fn now_usec() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as u64)
+}
+
+/// Formats a systemd microsecond timestamp in local time
+pub fn format_timestamp(usec: u64) -> String {
+    format_local(usec, "%a %Y-%m-%d %H:%M:%S")
+}
+
+/// Formats a systemd microsecond timestamp in local time with a strftime `format`
+pub fn format_local(usec: u64, format: &str) -> String {
+    match Local.timestamp_micros(usec as i64).single() {
+        Some(dt) => dt.format(format).to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Formats a microsecond span the way systemctl does, e.g. "1h 5min" or "3 days"
+pub fn format_duration(usec: u64) -> String {
+    let secs = usec / USEC_PER_SEC;
+    let (days, hours, mins) = (secs / 86_400, (secs % 86_400) / 3_600, (secs % 3_600) / 60);
+
+    if days > 0 {
+        if hours > 0 {
+            format!("{} day{} {}h", days, if days == 1 { "" } else { "s" }, hours)
+        } else {
+            format!("{} day{}", days, if days == 1 { "" } else { "s" })
+        }
+    } else if hours > 0 {
+        format!("{}h {}min", hours, mins)
+    } else if mins > 0 {
+        format!("{}min {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
@@ -2,20 +2,30 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
-use crate::app::{App, STATUS_OPTIONS};
+use crate::app::{
+    App, PanelMode, View, FLASH_DURATION, TIME_PRESETS, UNIT_TYPE_OPTIONS,
+};
+use crate::backend::SystemdBackend;
+use crate::journal::{boot_label, LogColumn, PRIORITY_NAMES};
+use crate::properties::GROUP_MARKER;
+use crate::service::{ServiceAction, UNIT_FILE_DROP_IN_MARKER, UNIT_FILE_MAIN_MARKER};
+use crate::time::{format_duration, format_timestamp, now_usec};
 
-/// Layout regions for mouse hit testing
+/// Screen regions of the main view; the single source of truth for rendering,
+/// mouse hit testing and page sizes
 pub struct LayoutRegions {
+    pub header: Rect,
     pub services_list: Rect,
     pub logs_panel: Option<Rect>,
+    pub footer: Rect,
 }
 
-/// Get layout regions for mouse hit testing
-pub fn get_layout_regions(area: Rect, show_logs: bool) -> LayoutRegions {
+/// Splits the screen into header, list (plus logs panel if shown) and footer
+pub fn get_layout_regions(area: Rect, show_logs: bool, list_width: u16) -> LayoutRegions {
     let chunks = Layout::vertical([
         Constraint::Length(3),
         Constraint::Min(1),
@@ -25,8 +35,8 @@ pub fn get_layout_regions(area: Rect, show_logs: bool) -> LayoutRegions {
 
     let (services_area, logs_area) = if show_logs {
         let middle = Layout::horizontal([
-            Constraint::Percentage(40),
-            Constraint::Percentage(60),
+            Constraint::Percentage(list_width),
+            Constraint::Percentage(100 - list_width),
         ])
         .split(chunks[1]);
         (middle[0], Some(middle[1]))
@@ -35,35 +45,21 @@ pub fn get_layout_regions(area: Rect, show_logs: bool) -> LayoutRegions {
     };
 
     LayoutRegions {
+        header: chunks[0],
         services_list: services_area,
         logs_panel: logs_area,
+        footer: chunks[2],
     }
 }
 
-pub fn render(frame: &mut Frame, app: &mut App) {
-    // Load logs for selected service if selection changed (only if logs are visible)
-    if app.show_logs {
-        app.load_logs_for_selected();
+pub fn render<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    if app.view == View::Timers {
+        render_timers(frame, app);
+        return;
     }
 
-    let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Min(1),
-        Constraint::Length(3),
-    ])
-    .split(frame.area());
-
-    // Conditionally split middle section for logs panel
-    let (services_area, logs_area) = if app.show_logs {
-        let middle_chunks = Layout::horizontal([
-            Constraint::Percentage(40),
-            Constraint::Percentage(60),
-        ])
-        .split(chunks[1]);
-        (middle_chunks[0], Some(middle_chunks[1]))
-    } else {
-        (chunks[1], None)
-    };
+    let regions = get_layout_regions(frame.area(), app.show_logs, app.list_width);
+    let (services_area, logs_area) = (regions.services_list, regions.logs_panel);
 
     // Header / Search bar
     let header = if app.log_search_mode {
@@ -106,16 +102,32 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         if let Some(ref status) = app.status_filter {
             info_parts.push(format!("Status: {}", status));
         }
-        let info = format!("{} ({} matches)", info_parts.join(" | "), app.filtered_indices.len());
+        let info = format!(
+            "[{}]{}{} {} ({} matches){}",
+            app.backend.scope().label(),
+            source_info(app),
+            boot_info(app),
+            info_parts.join(" | "),
+            app.filtered_indices.len(),
+            loading_info(app.services_loading || app.jobs_running > 0)
+        );
         Paragraph::new(info)
             .style(Style::default().fg(Color::Green))
             .block(Block::default().borders(Borders::ALL))
     } else {
-        Paragraph::new("SystemD Services")
+        Paragraph::new(format!(
+            "SystemD {} [{}]{}{}{}{}",
+            app.unit_type_title(),
+            app.backend.scope().label(),
+            source_info(app),
+            boot_info(app),
+            refresh_info(app),
+            loading_info(app.services_loading || app.jobs_running > 0)
+        ))
             .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
             .block(Block::default().borders(Borders::ALL))
     };
-    frame.render_widget(header, chunks[0]);
+    frame.render_widget(header, regions.header);
 
     // Services list
     if let Some(ref error) = app.error {
@@ -129,27 +141,66 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             .iter()
             .map(|&i| &app.services[i])
             .map(|service| {
-                let status_color = service.status_color();
-                let line = Line::from(vec![
-                    Span::styled(
-                        format!("{:8}", service.status_display()),
-                        Style::default().fg(status_color),
-                    ),
-                    Span::styled(&service.unit, Style::default().fg(Color::White)),
-                ]);
-                ListItem::new(line)
+                let status_color = app.status_color(service);
+                let change = app.changes.get(&service.unit);
+                let marker = match change {
+                    Some(c) if c.count > 1 => format!("●{:<2}", c.count.min(99)),
+                    Some(_) => "●  ".to_string(),
+                    None => "   ".to_string(),
+                };
+                let marker = Span::styled(marker, Style::default().fg(Color::Magenta));
+                let line = if app.show_logs {
+                    // The narrow pane next to the side panel only fits the name and state
+                    Line::from(vec![
+                        marker,
+                        Span::styled(&service.unit, Style::default().fg(Color::White)),
+                        Span::styled(
+                            format!(" {}", service.status_display()),
+                            Style::default().fg(status_color),
+                        ),
+                    ])
+                } else {
+                    Line::from(vec![
+                        marker,
+                        Span::styled(
+                            format!("{:10}", service.status_display()),
+                            Style::default().fg(status_color),
+                        ),
+                        Span::styled(
+                            format!("{:9.9} ", service.enablement_display()),
+                            Style::default().fg(service.enablement_color()),
+                        ),
+                        Span::styled(
+                            format!("{:10}", service.unit_type()),
+                            Style::default().fg(Color::Blue),
+                        ),
+                        Span::styled(&service.unit, Style::default().fg(Color::White)),
+                    ])
+                };
+                match change {
+                    Some(c) if c.last_changed.elapsed() < FLASH_DURATION => ListItem::new(line)
+                        .style(Style::default().add_modifier(Modifier::REVERSED)),
+                    _ => ListItem::new(line),
+                }
             })
             .collect();
 
-        let title = if app.search_query.is_empty() && app.status_filter.is_none() {
-            format!("Services ({})", app.services.len())
+        let mut title = if app.search_query.is_empty() && app.status_filter.is_none() {
+            format!("{} ({})", app.unit_type_title(), app.services.len())
         } else {
             format!(
-                "Services ({}/{})",
+                "{} ({}/{})",
+                app.unit_type_title(),
                 app.filtered_indices.len(),
                 app.services.len()
             )
         };
+        if !app.changes.is_empty() {
+            title.push_str(&format!(" [{} changed]", app.changes.len()));
+        }
+        if let Some(change) = app.selected_service().and_then(|s| app.changes.get(&s.unit)) {
+            title.push_str(&format!(" [selected was {}]", change.from));
+        }
 
         let services_border_style = if app.show_logs {
             Style::default().fg(Color::DarkGray)
@@ -176,10 +227,19 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Logs panel (only if visible)
     if let Some(logs_area) = logs_area {
-        let logs_title = if let Some(ref service_name) = app.last_selected_service {
-            format!("Logs: {}", service_name)
-        } else {
-            "Logs".to_string()
+        let logs_title = match app.panel_mode {
+            PanelMode::UnitFile => match app.selected_service() {
+                Some(service) => format!("Unit file: {}", service.unit),
+                None => "Unit file".to_string(),
+            },
+            PanelMode::Properties => match app.selected_service() {
+                Some(service) => format!("Properties: {}", service.unit),
+                None => "Properties".to_string(),
+            },
+            PanelMode::Logs => match app.last_selected_service {
+                Some(ref service_name) => format!("Logs: {}", service_name),
+                None => "Logs".to_string(),
+            },
         };
 
         let focused_suffix = " [FOCUSED]";
@@ -187,24 +247,72 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         // Calculate visible area (subtract 2 for borders)
         let visible_lines = logs_area.height.saturating_sub(2) as usize;
 
+        let mut filter_parts = Vec::new();
+        if app.panel_mode == PanelMode::Logs {
+            if let Some(window) = app.log_window_label() {
+                filter_parts.push(window);
+            }
+            if let Some(p) = app.log_priority {
+                filter_parts.push(format!("priority <= {}", PRIORITY_NAMES[p as usize]));
+            }
+        }
+        let priority_info = if filter_parts.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", filter_parts.join(" | "))
+        };
+
+        let follow_info = if app.follower.is_none() {
+            ""
+        } else if app.follow_paused {
+            " [paused]"
+        } else {
+            " [following]"
+        };
+
         // Create log content with scroll and search highlighting
-        let log_lines: Vec<Line> = app
-            .logs
+        let current_line = app.current_panel_line();
+        let panel_lines = app.panel_lines();
+        let log_lines: Vec<Line> = panel_lines
             .iter()
             .enumerate()
             .skip(app.logs_scroll)
             .take(visible_lines)
             .map(|(line_idx, line)| {
-                highlight_search_in_line(line, line_idx, app)
+                let priority = match app.panel_mode {
+                    PanelMode::Logs => app.logs.get(line_idx).and_then(|e| e.priority),
+                    _ => None,
+                };
+                let base_style = priority_style(priority);
+                if line_matches_search(line, app) {
+                    return highlight_search_in_line(line, line_idx, app).style(base_style);
+                }
+                match app.panel_mode {
+                    PanelMode::UnitFile => highlight_unit_file_line(line),
+                    PanelMode::Properties => highlight_property_line(line, line_idx),
+                    // Mark the line Enter opens when no search match is current
+                    PanelMode::Logs if Some(line_idx) == current_line => {
+                        Line::from(line.to_string()).style(base_style.bg(Color::DarkGray))
+                    }
+                    PanelMode::Logs => Line::from(line.to_string()).style(base_style),
+                }
             })
             .collect();
 
-        let scroll_info = if !app.logs.is_empty() {
+        let scroll_info = if !panel_lines.is_empty() {
+            let more = match (app.logs_has_older, app.logs_has_newer) {
+                _ if app.panel_mode != PanelMode::Logs => "",
+                (true, true) => ", more above and below",
+                (true, false) => ", more above",
+                (false, true) => ", more below",
+                (false, false) => "",
+            };
             format!(
-                " [{}-{}/{}]",
+                " [{}-{}/{}{}]",
                 app.logs_scroll + 1,
-                (app.logs_scroll + visible_lines).min(app.logs.len()),
-                app.logs.len()
+                (app.logs_scroll + visible_lines).min(panel_lines.len()),
+                panel_lines.len(),
+                more
             )
         } else {
             String::new()
@@ -217,7 +325,18 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("{}{}{}", logs_title, focused_suffix, scroll_info))
+                    .title(format!(
+                        "{}{}{}{}{}{}",
+                        logs_title,
+                        focused_suffix,
+                        priority_info,
+                        follow_info,
+                        scroll_info,
+                        loading_info(match app.panel_mode {
+                            PanelMode::Logs => app.logs_loading || app.logs_paging,
+                            _ => app.panel_loading,
+                        })
+                    ))
                     .border_style(border_style),
             )
             .wrap(Wrap { trim: false });
@@ -225,37 +344,315 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         frame.render_widget(logs_paragraph, logs_area);
     }
 
-    // Footer with keybindings
+    // Footer with keybindings, naming the keys configured in `[keys]`
+    let k = |action| app.keymap.key(action);
     let footer_text = if app.log_search_mode {
-        "Type to search logs | Esc/Enter: Exit search | ?: Help"
+        "Type to search logs | Esc/Enter: Exit search | ?: Help".to_string()
+    } else if app.show_logs && app.panel_mode == PanelMode::UnitFile {
+        format!(
+            "{}: Close unit file | {}/{}: Scroll | {}/{}: Top/Bottom | {}: Search | {}/{}: Next/Prev | ?: Help",
+            k("logs"),
+            k("down"),
+            k("up"),
+            k("top"),
+            k("bottom"),
+            k("search"),
+            k("next_match"),
+            k("previous_match")
+        )
+    } else if app.show_logs && app.panel_mode == PanelMode::Properties {
+        format!(
+            "{}: Close properties | {}/{}: Scroll | {}: Search | {}/{}: Copy line/all | ?: Help",
+            k("logs"),
+            k("down"),
+            k("up"),
+            k("search"),
+            k("copy_line"),
+            k("copy_all")
+        )
     } else if app.show_logs && !app.log_search_query.is_empty() {
-        "l: Exit logs | j/k: Scroll | n/N: Next/Prev match | Esc: Clear | ?: Help"
+        format!(
+            "{}: Exit logs | {}/{}: Scroll | {}/{}: Next/Prev match | Esc: Clear | ?: Help",
+            k("logs"),
+            k("down"),
+            k("up"),
+            k("next_match"),
+            k("previous_match")
+        )
     } else if app.show_logs {
-        "l: Exit logs | j/k: Scroll | g/G: Top/Bottom | /: Search logs | ?: Help"
+        format!(
+            "{}: Exit | {}: Search | {}: Follow | {}: Priority | {}: Time | {}: Columns | Enter: Entry | ?: Help",
+            k("logs"),
+            k("search"),
+            k("follow"),
+            k("priority"),
+            k("time_range"),
+            k("columns")
+        )
     } else if app.search_mode {
-        "Type to search | Esc/Enter: Exit search | ?: Help"
+        "Type to search | Esc/Enter: Exit search | ?: Help".to_string()
     } else if !app.search_query.is_empty() || app.status_filter.is_some() {
-        "q: Quit | /: Search | s: Status | l: Logs | Esc: Clear | ?: Help"
+        format!(
+            "{}: Quit | {}: Search | {}: Status | {}: Type | {}: Actions | {}: Logs | Esc: Clear | ?: Help",
+            k("quit"),
+            k("search"),
+            k("status_filter"),
+            k("type_filter"),
+            k("actions"),
+            k("logs")
+        )
     } else {
-        "q/Esc: Quit | /: Search | s: Status | l: Logs | ?: Help"
+        format!(
+            "{}/Esc: Quit | {}: Search | {}: Status | {}: Type | {}: Actions | {}: Logs | {}: User/System | ?: Help",
+            k("quit"),
+            k("search"),
+            k("status_filter"),
+            k("type_filter"),
+            k("actions"),
+            k("logs"),
+            k("scope")
+        )
     };
-    let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(footer, chunks[2]);
+    let footer = if let Some(ref message) = app.message {
+        let color = if message.is_error { Color::Red } else { Color::Green };
+        Paragraph::new(message.text.as_str()).style(Style::default().fg(color))
+    } else {
+        Paragraph::new(footer_text).style(Style::default().fg(Color::DarkGray))
+    }
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, regions.footer);
 
     // Status picker overlay
     if app.show_status_picker {
         render_status_picker(frame, app);
     }
 
+    // Unit type picker overlay
+    if app.show_type_picker {
+        render_type_picker(frame, app);
+    }
+
+    // Action picker overlay
+    if app.show_action_picker {
+        render_action_picker(frame, app);
+    }
+
+    // Dependency tree overlay
+    if app.deps.is_some() {
+        render_deps(frame, app);
+    }
+
+    // Log column picker overlay
+    if app.show_column_picker {
+        render_column_picker(frame, app);
+    }
+
+    // Log priority picker overlay
+    if app.show_priority_picker {
+        render_priority_picker(frame, app);
+    }
+
+    // Boot picker overlay
+    if app.show_boot_picker {
+        render_boot_picker(frame, app);
+    }
+
+    // Log time range overlays
+    if app.show_time_picker {
+        render_time_picker(frame, app);
+    }
+    if let Some(ref input) = app.time_input {
+        render_time_input(frame, input);
+    }
+
+    // Log entry detail overlay
+    if let Some(index) = app.entry_detail {
+        render_entry_detail(frame, app, index);
+    }
+
+    // Action confirm dialog
+    if let Some((ref unit, action)) = app.pending_action {
+        render_action_confirm(frame, unit, action);
+    }
+
     // Help overlay
     if app.show_help {
         render_help(frame, app);
     }
 }
 
-fn highlight_search_in_line<'a>(line: &str, line_idx: usize, app: &App) -> Line<'a> {
+fn render_timers<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let regions = get_layout_regions(frame.area(), false, app.list_width);
+
+    let header = Paragraph::new(format!(
+        "SystemD Timers [{}]{}",
+        app.backend.scope().label(),
+        loading_info(app.timers_loading)
+    ))
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(header, regions.header);
+
+    if let Some(ref error) = app.timers_error {
+        let error_msg = Paragraph::new(error.as_str())
+            .style(Style::default().fg(Color::Red))
+            .block(Block::default().borders(Borders::ALL).title("Error"));
+        frame.render_widget(error_msg, regions.services_list);
+    } else {
+        let now = now_usec();
+        let rows: Vec<Row> = app
+            .timers
+            .iter()
+            .map(|timer| {
+                let next = timer.next_elapse().map_or("-".to_string(), format_timestamp);
+                let left = timer.left(now).map_or("-".to_string(), format_duration);
+                let last = timer.last_trigger().map_or("-".to_string(), format_timestamp);
+                let passed = timer
+                    .passed(now)
+                    .map_or("-".to_string(), |p| format!("{} ago", format_duration(p)));
+                Row::new(vec![
+                    Cell::from(next).style(Style::default().fg(Color::Cyan)),
+                    Cell::from(left).style(Style::default().fg(Color::Green)),
+                    Cell::from(last),
+                    Cell::from(passed).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(timer.unit.as_str()),
+                    Cell::from(timer.activates.as_str()).style(Style::default().fg(Color::Yellow)),
+                ])
+            })
+            .collect();
+
+        let header_row = Row::new(vec!["NEXT", "LEFT", "LAST", "PASSED", "UNIT", "ACTIVATES"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(12),
+                Constraint::Length(24),
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header_row)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Timers ({})", app.timers.len())),
+        )
+        .row_highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+        frame.render_stateful_widget(table, regions.services_list, &mut app.timers_state);
+    }
+
+    let footer = if let Some(ref message) = app.message {
+        let color = if message.is_error { Color::Red } else { Color::Green };
+        Paragraph::new(message.text.as_str()).style(Style::default().fg(color))
+    } else {
+        let k = |action| app.keymap.key(action);
+        Paragraph::new(format!(
+            "{}: Quit | Enter: Logs of activated unit | {}: Refresh | {}/Esc: Back | ?: Help",
+            k("quit"),
+            k("refresh"),
+            k("timers")
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+    }
+    .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, regions.footer);
+
+    if app.show_help {
+        render_help(frame, app);
+    }
+}
+
+/// Base style of a journal line: errors and worse in red, warnings in yellow, debug dimmed
+fn priority_style(priority: Option<u8>) -> Style {
+    match priority {
+        Some(0..=3) => Style::default().fg(Color::Red),
+        Some(4) => Style::default().fg(Color::Yellow),
+        Some(5) => Style::default().add_modifier(Modifier::BOLD),
+        Some(7) => Style::default().fg(Color::DarkGray),
+        _ => Style::default(),
+    }
+}
+
+fn line_matches_search<B: SystemdBackend>(line: &str, app: &App<B>) -> bool {
+    !app.log_search_query.is_empty()
+        && line
+            .to_lowercase()
+            .contains(&app.log_search_query.to_lowercase())
+}
+
+/// Syntax highlighting for `systemctl cat` output: file markers, sections, keys and comments
+fn highlight_unit_file_line<'a>(line: &str) -> Line<'a> {
+    let trimmed = line.trim_start();
+
+    if line.starts_with(UNIT_FILE_MAIN_MARKER) {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ))
+    } else if line.starts_with(UNIT_FILE_DROP_IN_MARKER) {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ))
+    } else if trimmed.starts_with('[') && trimmed.trim_end().ends_with(']') {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))
+    } else if trimmed.starts_with('#') || trimmed.starts_with(';') {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else if let Some((key, value)) = line.split_once('=') {
+        Line::from(vec![
+            Span::styled(key.to_string(), Style::default().fg(Color::Green)),
+            Span::styled("=", Style::default().fg(Color::DarkGray)),
+            Span::raw(value.to_string()),
+        ])
+    } else {
+        Line::from(line.to_string())
+    }
+}
+
+/// Highlighting for the properties panel: summary, group headings and Key=Value lines
+fn highlight_property_line<'a>(line: &str, line_idx: usize) -> Line<'a> {
+    if line_idx == 0 {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some(group) = line.strip_prefix(GROUP_MARKER) {
+        Line::from(Span::styled(
+            group.to_string(),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some((key, value)) = line.split_once('=') {
+        Line::from(vec![
+            Span::styled(key.to_string(), Style::default().fg(Color::Green)),
+            Span::styled("=", Style::default().fg(Color::DarkGray)),
+            Span::raw(value.to_string()),
+        ])
+    } else {
+        Line::from(line.to_string())
+    }
+}
+
+fn highlight_search_in_line<'a, B: SystemdBackend>(
+    line: &str,
+    line_idx: usize,
+    app: &App<B>,
+) -> Line<'a> {
     if app.log_search_query.is_empty() {
         return Line::from(line.to_string());
     }
@@ -304,53 +701,83 @@ fn highlight_search_in_line<'a>(line: &str, line_idx: usize, app: &App) -> Line<
     Line::from(spans)
 }
 
-fn render_help(frame: &mut Frame, app: &App) {
+fn render_help<B: SystemdBackend>(frame: &mut Frame, app: &App<B>) {
     let section_style = Style::default()
         .fg(Color::Yellow)
         .add_modifier(Modifier::BOLD);
+    let k = |action| app.keymap.key(action);
 
     let mut help_text = vec![
         Line::from(vec![Span::styled("Navigation", section_style)]),
-        Line::from("  j / Down      Move down"),
-        Line::from("  k / Up        Move up"),
-        Line::from("  g / Home      Go to top"),
-        Line::from("  G / End       Go to bottom"),
+        help_line(format!("{} / Down", k("down")), "Move down"),
+        help_line(format!("{} / Up", k("up")), "Move up"),
+        help_line(format!("{} / Home", k("top")), "Go to top"),
+        help_line(format!("{} / End", k("bottom")), "Go to bottom"),
         Line::from(""),
         Line::from(vec![Span::styled("Search & Filter", section_style)]),
-        Line::from("  /             Start search"),
-        Line::from("  s             Open status filter"),
-        Line::from("  Esc           Clear search/filter"),
+        help_line(k("search"), "Start search"),
+        help_line(k("status_filter"), "Open status filter"),
+        help_line(k("type_filter"), "Open unit type picker"),
+        help_line("Esc", "Clear search/filter"),
         Line::from(""),
         Line::from(vec![Span::styled("Logs Panel", section_style)]),
-        Line::from("  l             Toggle logs panel"),
-        Line::from("  PgUp/PgDn     Scroll list/logs"),
-        Line::from("  Ctrl+u/d      Scroll logs half page"),
+        help_line(k("logs"), "Toggle logs panel"),
+        help_line(k("unit_file"), "View unit file (systemctl cat)"),
+        help_line(k("properties"), "View properties (systemctl show)"),
+        help_line(k("dependencies"), "Dependency tree (Tab: direction)"),
+        help_line("PgUp/PgDn", "Scroll list/logs"),
+        help_line("Ctrl+u/d", "Scroll logs half page"),
         Line::from(""),
     ];
 
     if app.show_logs {
         help_text.extend(vec![
             Line::from(vec![Span::styled("Log Focus Mode", section_style)]),
-            Line::from("  j/k / Up/Down Scroll logs"),
-            Line::from("  g / Home      Go to top of logs"),
-            Line::from("  G / End       Go to bottom of logs"),
-            Line::from("  /             Search within logs"),
-            Line::from("  n / N         Next/Prev search match"),
-            Line::from("  l             Exit log mode"),
-            Line::from("  Esc           Clear log search"),
+            help_line(format!("{}/{} / Up/Down", k("down"), k("up")), "Scroll logs"),
+            help_line(format!("{} / Home", k("top")), "Go to top of logs"),
+            help_line(format!("{} / End", k("bottom")), "Go to bottom of logs"),
+            help_line(k("search"), "Search within logs"),
+            help_line(
+                format!("{} / {}", k("next_match"), k("previous_match")),
+                "Next/Prev search match",
+            ),
+            help_line(
+                format!("{} / {}", k("copy_line"), k("copy_all")),
+                "Copy current line / whole panel",
+            ),
+            help_line(k("follow"), "Follow new log entries live"),
+            help_line(k("columns"), "Choose log columns"),
+            help_line(k("priority"), "Filter by priority (journalctl -p)"),
+            help_line(k("time_range"), "Time range (--since/--until)"),
+            help_line(k("boots"), "Pick boot (journalctl -b)"),
+            help_line("Enter", "Show all fields of the marked entry"),
+            help_line(k("logs"), "Exit log mode"),
+            help_line("Esc", "Clear log search"),
             Line::from(""),
         ]);
     }
 
     help_text.extend(vec![
+        Line::from(vec![Span::styled("Service Actions", section_style)]),
+        help_line(k("actions"), "Start/stop/restart/reload"),
+        help_line("", "Enable/disable/mask/unmask"),
+        help_line(k("edit"), "Edit override drop-in in $EDITOR"),
+        Line::from(""),
         Line::from(vec![Span::styled("Mouse", section_style)]),
-        Line::from("  Click         Select service"),
-        Line::from("  Scroll        Navigate list/logs"),
+        help_line("Click", "Select service"),
+        help_line("Scroll", "Navigate list/logs"),
         Line::from(""),
         Line::from(vec![Span::styled("Other", section_style)]),
-        Line::from("  r             Refresh services"),
-        Line::from("  ?             Toggle this help"),
-        Line::from("  q / Esc       Quit"),
+        help_line(k("refresh"), "Refresh services"),
+        help_line(k("auto_refresh"), "Cycle auto-refresh (off/2s/5s/10s/30s)"),
+        help_line(
+            format!("{} / {}", k("acknowledge"), k("acknowledge_all")),
+            "Acknowledge state change of selected / all",
+        ),
+        help_line(k("scope"), "Toggle system/user manager"),
+        help_line(k("timers"), "Timers dashboard"),
+        help_line("?", "Toggle this help"),
+        help_line(format!("{} / Esc", k("quit")), "Quit"),
     ]);
 
     let area = centered_rect(50, 70, frame.area());
@@ -360,7 +787,7 @@ fn render_help(frame: &mut Frame, app: &App) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Help")
+                .title(format!("Help (backend: {})", app.backend.label()))
                 .style(Style::default().bg(Color::Black)),
         );
 
@@ -368,12 +795,18 @@ fn render_help(frame: &mut Frame, app: &App) {
     frame.render_widget(help, area);
 }
 
-fn render_status_picker(frame: &mut Frame, app: &mut App) {
-    let items: Vec<ListItem> = STATUS_OPTIONS
-        .iter()
-        .map(|&opt| {
+/// A help row: keys in a fixed-width column, then what they do
+fn help_line(keys: impl ToString, description: &str) -> Line<'static> {
+    Line::from(format!("  {:<14}{}", keys.to_string(), description))
+}
+
+fn render_status_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = std::iter::once("All")
+        .chain(app.status_options.iter().map(String::as_str))
+        .map(|opt| {
             let color = match opt {
                 "All" => Color::Cyan,
+                _ if app.status_colors.contains_key(opt) => app.status_colors[opt],
                 "running" => Color::Green,
                 "exited" => Color::Yellow,
                 "failed" => Color::Red,
@@ -404,11 +837,385 @@ fn render_status_picker(frame: &mut Frame, app: &mut App) {
                 .add_modifier(Modifier::BOLD),
         );
 
-    let area = centered_fixed_rect(30, STATUS_OPTIONS.len() as u16 + 2, frame.area());
+    let area = centered_fixed_rect(30, app.status_options.len() as u16 + 3, frame.area());
     frame.render_widget(Clear, area);
     frame.render_stateful_widget(list, area, &mut app.status_picker_state);
 }
 
+fn render_type_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = UNIT_TYPE_OPTIONS
+        .iter()
+        .map(|&opt| {
+            let color = if opt == "All" { Color::Cyan } else { Color::Blue };
+            let is_active = match (&app.unit_type, opt) {
+                (None, "All") => true,
+                (Some(t), o) => t == o,
+                _ => false,
+            };
+            let marker = if is_active { " *" } else { "" };
+            let text = format!("  {}{}", opt, marker);
+            ListItem::new(text).style(Style::default().fg(color))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Unit Type")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(30, UNIT_TYPE_OPTIONS.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.type_picker_state);
+}
+
+fn render_deps<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let Some(ref tree) = app.deps else { return };
+
+    let items: Vec<ListItem> = tree
+        .nodes
+        .iter()
+        .map(|node| {
+            let marker = if node.cycle {
+                "↻ "
+            } else if node.expanded {
+                "▾ "
+            } else if node.depth == 0 || node.service.load != "not-found" {
+                "▸ "
+            } else {
+                "  "
+            };
+            let line = Line::from(vec![
+                Span::raw("  ".repeat(node.depth)),
+                Span::styled(marker, Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    node.service.unit.clone(),
+                    Style::default().fg(app.status_color(&node.service)),
+                ),
+                Span::styled(
+                    format!(" ({})", node.service.status_display()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            ListItem::new(line)
+        })
+        .collect();
+
+    let title = format!(
+        "{} [{}]{} | Tab: Direction | Space: Expand | Enter: Select",
+        tree.root().unit,
+        tree.direction.label(),
+        loading_info(app.deps_loading)
+    );
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_rect(70, 80, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.deps_state);
+}
+
+fn render_priority_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let options = std::iter::once(None).chain((0..PRIORITY_NAMES.len() as u8).map(Some));
+    let items: Vec<ListItem> = options
+        .map(|priority| {
+            let (label, color) = match priority {
+                None => ("All", Color::Cyan),
+                Some(p) => (
+                    PRIORITY_NAMES[p as usize],
+                    priority_style(Some(p)).fg.unwrap_or(Color::White),
+                ),
+            };
+            let marker = if priority == app.log_priority { " *" } else { "" };
+            ListItem::new(format!("  {}{}", label, marker)).style(Style::default().fg(color))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Log Priority")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(30, PRIORITY_NAMES.len() as u16 + 3, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.priority_picker_state);
+}
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Title suffix with a spinner while a background load is running
+fn loading_info(loading: bool) -> String {
+    if !loading {
+        return String::new();
+    }
+    let frame = (now_usec() / 100_000) as usize % SPINNER.len();
+    format!(" {} loading", SPINNER[frame])
+}
+
+/// Header suffix showing the auto-refresh interval, if any
+fn refresh_info<B: SystemdBackend>(app: &App<B>) -> String {
+    app.refresh_interval
+        .map(|interval| format!(" [auto-refresh {}s]", interval.as_secs()))
+        .unwrap_or_default()
+}
+
+/// Header suffix marking units that do not come from the running system
+fn source_info<B: SystemdBackend>(app: &App<B>) -> String {
+    if app.backend.read_only() {
+        format!(" [read-only {}]", app.backend.label())
+    } else {
+        String::new()
+    }
+}
+
+/// Header suffix naming the boot the logs are scoped to
+fn boot_info<B: SystemdBackend>(app: &App<B>) -> String {
+    app.log_boot
+        .map(|boot| format!(" [{}]", boot_label(boot)))
+        .unwrap_or_default()
+}
+
+fn render_boot_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = std::iter::once(ListItem::new("  All boots"))
+        .chain(app.boots.iter().map(|boot| {
+            let marker = if Some(boot.index) == app.log_boot { " *" } else { "" };
+            ListItem::new(format!(
+                "  {:>4}  {:.12}  {} - {}{}",
+                boot.index,
+                boot.boot_id,
+                format_timestamp(boot.first_entry),
+                format_timestamp(boot.last_entry),
+                marker
+            ))
+        }))
+        .map(|item| item.style(Style::default().fg(Color::Cyan)))
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Boot (journalctl -b)")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(80, app.boots.len() as u16 + 3, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.boot_picker_state);
+}
+
+fn render_time_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = TIME_PRESETS
+        .iter()
+        .map(|&preset| {
+            ListItem::new(format!("  {}", preset)).style(Style::default().fg(Color::Cyan))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Time Range")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(30, TIME_PRESETS.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.time_picker_state);
+}
+
+fn render_time_input(frame: &mut Frame, input: &str) {
+    let text = vec![
+        Line::from(Span::styled(
+            format!("{}_", input),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "since..until, e.g. -1h, yesterday..today, 2024-05-01 10:00",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            "Enter: Apply | Esc: Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let prompt = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Custom Time Range")
+            .style(Style::default().bg(Color::Black)),
+    );
+
+    let area = centered_fixed_rect(64, 6, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(prompt, area);
+}
+
+fn render_column_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = LogColumn::ALL
+        .iter()
+        .map(|column| {
+            let checked = if app.log_columns.contains(column) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            ListItem::new(format!("  {} {}", checked, column.label()))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Log Columns")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(30, LogColumn::ALL.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.column_picker_state);
+}
+
+fn render_entry_detail<B: SystemdBackend>(frame: &mut Frame, app: &App<B>, index: usize) {
+    let Some(entry) = app.logs.get(index) else {
+        return;
+    };
+
+    let lines: Vec<Line> = entry
+        .fields
+        .iter()
+        .map(|(key, value)| {
+            Line::from(vec![
+                Span::styled(key.clone(), Style::default().fg(Color::Green)),
+                Span::styled("=", Style::default().fg(Color::DarkGray)),
+                Span::raw(value.clone()),
+            ])
+        })
+        .collect();
+
+    let detail = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Entry ({} fields) | j/k: Scroll | Esc: Close", entry.fields.len()))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.entry_detail_scroll as u16, 0));
+
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(detail, area);
+}
+
+fn render_action_picker<B: SystemdBackend>(frame: &mut Frame, app: &mut App<B>) {
+    let items: Vec<ListItem> = ServiceAction::ALL
+        .iter()
+        .map(|action| {
+            let color = match action {
+                ServiceAction::Start
+                | ServiceAction::Enable
+                | ServiceAction::Unmask
+                | ServiceAction::EnableNow => Color::Green,
+                ServiceAction::Stop | ServiceAction::Disable | ServiceAction::Mask => Color::Red,
+                _ => Color::Yellow,
+            };
+            ListItem::new(format!("  {}", action.label())).style(Style::default().fg(color))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Action")
+                .style(Style::default().bg(Color::Black)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let area = centered_fixed_rect(30, ServiceAction::ALL.len() as u16 + 2, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut app.action_picker_state);
+}
+
+fn render_action_confirm(frame: &mut Frame, unit: &str, action: ServiceAction) {
+    let question = format!("{} {}?", action.label(), unit);
+
+    let text = vec![
+        Line::from(question.clone()),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y/Enter: Confirm | n/Esc: Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let dialog = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Confirm")
+                .style(Style::default().bg(Color::Black)),
+        );
+
+    let width = (question.len() as u16 + 4).max(40);
+    let area = centered_fixed_rect(width, 5, frame.area());
+    frame.render_widget(Clear, area);
+    frame.render_widget(dialog, area);
+}
+
 fn centered_fixed_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + area.width.saturating_sub(width) / 2;
     let y = area.y + area.height.saturating_sub(height) / 2;
@@ -437,46 +1244,164 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
 }
 
 /// Returns the number of visible lines in the logs panel
-pub fn get_logs_visible_lines(frame: &Frame, show_logs: bool) -> usize {
-    if !show_logs {
-        return 0;
+pub fn get_logs_visible_lines(frame: &Frame, show_logs: bool, list_width: u16) -> usize {
+    get_layout_regions(frame.area(), show_logs, list_width)
+        .logs_panel
+        .map_or(0, |logs| logs.height.saturating_sub(2) as usize)
+}
+
+/// Returns the number of visible lines in the services list
+pub fn get_services_visible_lines(frame: &Frame, show_logs: bool, list_width: u16) -> usize {
+    get_layout_regions(frame.area(), show_logs, list_width)
+        .services_list
+        .height
+        .saturating_sub(2) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::app::LIST_WIDTH;
+    use crate::backend::fake::{loaded_app, sample, settle, FakeBackend};
+    use crate::journal::LogColumn;
+    use crate::keymap::Keymap;
+
+    const SIZES: [(u16, u16); 3] = [(60, 16), (80, 24), (120, 32)];
+
+    fn draw(app: &mut App<FakeBackend>, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| render(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| {
+                let line: String = (0..width).map(|x| buffer[(x, y)].symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
-    let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Min(1),
-        Constraint::Length(3),
-    ])
-    .split(frame.area());
+    /// Renders at every size in `SIZES`, one snapshot each
+    fn snapshot_sizes(name: &str, app: &mut App<FakeBackend>) {
+        for (width, height) in SIZES {
+            assert_snapshot!(format!("{}_{}x{}", name, width, height), draw(app, width, height));
+        }
+    }
 
-    let middle_chunks = Layout::horizontal([
-        Constraint::Percentage(40),
-        Constraint::Percentage(60),
-    ])
-    .split(chunks[1]);
+    fn app_with_logs() -> App<FakeBackend> {
+        let mut app = loaded_app(sample());
+        // Times depend on the local timezone
+        app.log_columns = vec![LogColumn::Identifier, LogColumn::Message];
+        app.toggle_logs();
+        settle(&mut app);
+        app
+    }
 
-    middle_chunks[1].height.saturating_sub(2) as usize
-}
+    #[test]
+    fn service_list() {
+        let mut app = loaded_app(sample());
+        app.next();
+        snapshot_sizes("service_list", &mut app);
+    }
 
-/// Returns the number of visible lines in the services list
-pub fn get_services_visible_lines(frame: &Frame, show_logs: bool) -> usize {
-    let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Min(1),
-        Constraint::Length(3),
-    ])
-    .split(frame.area());
+    #[test]
+    fn logs_panel() {
+        let mut app = app_with_logs();
+        snapshot_sizes("logs_panel", &mut app);
+    }
 
-    let services_area = if show_logs {
-        let middle_chunks = Layout::horizontal([
-            Constraint::Percentage(40),
-            Constraint::Percentage(60),
-        ])
-        .split(chunks[1]);
-        middle_chunks[0]
-    } else {
-        chunks[1]
-    };
+    #[test]
+    fn search_header() {
+        let mut app = loaded_app(sample());
+        app.search_mode = true;
+        app.search_query = "ss".to_string();
+        app.update_filter();
+        assert_snapshot!("search_typing", draw(&mut app, 80, 24));
+
+        app.search_mode = false;
+        app.status_filter = Some("running".to_string());
+        app.update_filter();
+        assert_snapshot!("search_applied", draw(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn log_search_header() {
+        let mut app = app_with_logs();
+        app.log_search_mode = true;
+        app.log_search_query = "job".to_string();
+        app.update_log_search();
+        assert_snapshot!("log_search", draw(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn status_picker_overlay() {
+        let mut app = loaded_app(sample());
+        app.open_status_picker();
+        app.status_picker_next();
+        snapshot_sizes("status_picker", &mut app);
+    }
+
+    #[test]
+    fn help_overlay() {
+        let mut app = loaded_app(sample());
+        app.toggle_help();
+        snapshot_sizes("help", &mut app);
+    }
 
-    services_area.height.saturating_sub(2) as usize
+    #[test]
+    fn hints_name_the_configured_keys() {
+        let mut app = loaded_app(sample());
+        let settings = [("quit", "Q"), ("down", "J"), ("follow", "F")]
+            .map(|(action, key)| (action.to_string(), key.to_string()))
+            .into();
+        app.keymap = Keymap::new(&settings).0;
+        assert!(draw(&mut app, 120, 32).contains("Q/Esc: Quit"));
+
+        app.toggle_help();
+        let help = draw(&mut app, 120, 32);
+        assert!(help.contains("J / Down      Move down"), "{}", help);
+
+        app.toggle_help();
+        app.toggle_logs();
+        settle(&mut app);
+        assert!(draw(&mut app, 120, 32).contains("F: Follow"));
+    }
+
+    /// Mouse hit testing and page sizes must agree with where things are drawn
+    #[test]
+    fn layout_regions_match_rendered_borders() {
+        for (width, height) in SIZES {
+            for show_logs in [false, true] {
+                let mut app = app_with_logs();
+                if !show_logs {
+                    app.toggle_logs();
+                }
+                let screen = draw(&mut app, width, height);
+                let rows: Vec<Vec<char>> = screen.lines().map(|l| l.chars().collect()).collect();
+                let corner = |rect: Rect| rows[rect.y as usize].get(rect.x as usize).copied();
+
+                let area = Rect::new(0, 0, width, height);
+                let regions = get_layout_regions(area, show_logs, LIST_WIDTH);
+                assert_eq!(corner(regions.header), Some('┌'));
+                assert_eq!(corner(regions.services_list), Some('┌'));
+                assert_eq!(corner(regions.footer), Some('┌'));
+                let title = &rows[regions.services_list.y as usize]
+                    [regions.services_list.x as usize + 1..];
+                assert!(title.iter().collect::<String>().starts_with("Services"));
+                match regions.logs_panel {
+                    Some(logs) => {
+                        assert_eq!(corner(logs), Some('┌'));
+                        let title: String = rows[logs.y as usize][logs.x as usize + 1..]
+                            .iter()
+                            .collect();
+                        assert!(title.starts_with("Logs: cron.service"));
+                    }
+                    None => assert!(!show_logs),
+                }
+            }
+        }
+    }
 }